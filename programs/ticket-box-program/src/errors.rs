@@ -30,4 +30,76 @@ pub enum ErrorCode {
     TokenTransferFailed,
     #[msg("Mint Mismatch!")]
     MintMismatch,
+    #[msg("Reached `ticket_per_wallet` limit")]
+    ExceededTicketPerWallet,
+    #[msg("Numerical overflow")]
+    NumericalOverflow,
+    #[msg("Sale not started")]
+    SaleNotStarted,
+    #[msg("Collection mint does not match the ticket box")]
+    CollectionMintMismatch,
+    #[msg("Payment destination is not the ticket box escrow")]
+    InvalidEscrow,
+    #[msg("Event not ended")]
+    EventNotEnded,
+    #[msg("Event cancelled")]
+    EventCancelled,
+    #[msg("Event not cancelled")]
+    EventNotCancelled,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Ticket token account does not hold the ticket")]
+    InvalidTicketTokenAccount,
+    #[msg("Ticket already checked in")]
+    AlreadyCheckedIn,
+    #[msg("Invalid operator permissions")]
+    InvalidOperatorPermissions,
+    #[msg("Ticket can't be thawed yet")]
+    ThawNotAllowed,
+    #[msg("Name is too long")]
+    NameTooLong,
+    #[msg("Uri is too long")]
+    UriTooLong,
+    #[msg("Reached the maximum number of ticket tiers")]
+    TooManyTicketTiers,
+    #[msg("Ticket box sells through tiers, pass one")]
+    TicketTierRequired,
+    #[msg("Ticket tier does not belong to the ticket box")]
+    TicketTierMismatch,
+    #[msg("Ticket tier sale ended")]
+    TicketTierSaleEnded,
+    #[msg("`num_of_tickets` is lower than the tickets sold")]
+    InvalidNumOfTickets,
+    #[msg("Ticket box has sales and the event has not ended")]
+    TicketBoxNotClosable,
+    #[msg("Escrow has not been withdrawn")]
+    EscrowNotWithdrawn,
+    #[msg("Account does not belong to the ticket box")]
+    InvalidRelatedAccount,
+    #[msg("Ticket box already uses the current layout")]
+    TicketBoxAlreadyMigrated,
+    #[msg("Uuid is too long")]
+    UuidTooLong,
+    #[msg("Ticket price is higher than the max price")]
+    PriceExceedsMaxPrice,
+    #[msg("Ticket box currency does not match the expected currency")]
+    CurrencyMismatch,
+    #[msg("Currency can't change after the first sale")]
+    CurrencyLocked,
+    #[msg("Fee can't exceed 10000 basis points")]
+    InvalidFeeBps,
+    #[msg("Treasury does not match the config")]
+    InvalidTreasury,
+    #[msg("Seller fee can't exceed 10000 basis points")]
+    InvalidSellerFeeBasisPoints,
+    #[msg("Creators must be 1 to 4 with shares summing to 100")]
+    InvalidCreators,
+    #[msg("Ticket box creator must sign custom ticket uris")]
+    CreatorSignatureRequired,
+    #[msg("Symbol is too long")]
+    SymbolTooLong,
+    #[msg("Ticket names from the name format can exceed 32 characters")]
+    NameFormatTooLong,
+    #[msg("Sale can't change once its proceeds are withdrawn")]
+    ProceedsAlreadyWithdrawn,
 }
@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+
+use crate::{CurrencyUpdate, OptionUpdate, TicketBoxCreator, UriMode};
+
+#[event]
+pub struct TicketBoxCreated {
+    pub ticket_box: Pubkey,
+    pub creator: Pubkey,
+    pub uuid: String,
+    pub name: String,
+    pub uri: String,
+    pub start_at: i64,
+    pub end_at: Option<i64>,
+    pub num_of_tickets: Option<i64>,
+    pub ticket_per_wallet: Option<i64>,
+    pub currency: Option<Pubkey>,
+    pub price: u64,
+    pub transferable: bool,
+    pub collection_mint: Pubkey,
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<TicketBoxCreator>,
+    pub uri_mode: UriMode,
+    pub symbol: String,
+    pub name_format: String,
+}
+
+// only the changed fields are set
+#[event]
+pub struct TicketBoxUpdated {
+    pub ticket_box: Pubkey,
+    pub authority: Pubkey,
+    pub name: Option<String>,
+    pub uri: Option<String>,
+    pub start_at: Option<i64>,
+    pub end_at: OptionUpdate,
+    pub num_of_tickets: OptionUpdate,
+    pub ticket_per_wallet: OptionUpdate,
+    pub price: Option<u64>,
+    pub transferable: Option<bool>,
+    pub currency: CurrencyUpdate,
+    pub seller_fee_basis_points: Option<u16>,
+    pub creators: Option<Vec<TicketBoxCreator>>,
+    pub uri_mode: Option<UriMode>,
+    pub symbol: Option<String>,
+    pub name_format: Option<String>,
+}
+
+#[event]
+pub struct TicketBoxCancelled {
+    pub ticket_box: Pubkey,
+    pub protocol_fees: u64, // put back in the vault by the creator
+}
+
+#[event]
+pub struct TicketBoxClosed {
+    pub ticket_box: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct TicketBoxMigrated {
+    pub ticket_box: Pubkey,
+    pub version: u8,
+}
+
+#[event]
+pub struct TicketTierAdded {
+    pub ticket_box: Pubkey,
+    pub ticket_tier: Pubkey,
+    pub index: u8,
+    pub name: String,
+    pub uri: String,
+    pub start_at: i64,
+    pub end_at: Option<i64>,
+    pub num_of_tickets: Option<i64>,
+    pub ticket_per_wallet: Option<i64>,
+    pub price: u64,
+}
+
+// only the changed fields are set
+#[event]
+pub struct TicketTierUpdated {
+    pub ticket_box: Pubkey,
+    pub ticket_tier: Pubkey,
+    pub name: Option<String>,
+    pub uri: Option<String>,
+    pub start_at: Option<i64>,
+    pub end_at: OptionUpdate,
+    pub num_of_tickets: OptionUpdate,
+    pub ticket_per_wallet: OptionUpdate,
+    pub price: Option<u64>,
+}
+
+#[event]
+pub struct TicketMinted {
+    pub ticket_box: Pubkey,
+    pub buyer: Pubkey,
+    pub ticket_mint: Pubkey,
+    pub serial: i64,
+    pub tier: Option<u8>,
+    pub price: u64,
+    pub protocol_fee: u64,
+    pub currency: Option<Pubkey>, // None for sol
+}
+
+// `name_format` and `uri` are None when the tickets kept their name or uri
+#[event]
+pub struct TicketMetadataUpdated {
+    pub ticket_box: Pubkey,
+    pub ticket_mints: Vec<Pubkey>,
+    pub name_format: Option<String>,
+    pub uri: Option<String>,
+}
+
+#[event]
+pub struct TicketCheckedIn {
+    pub ticket_box: Pubkey,
+    pub ticket_mint: Pubkey,
+    pub authority: Pubkey,
+    pub checked_in_at: i64,
+}
+
+#[event]
+pub struct TicketThawed {
+    pub ticket_box: Pubkey,
+    pub ticket_mint: Pubkey,
+    pub holder: Pubkey,
+}
+
+#[event]
+pub struct TicketRefunded {
+    pub ticket_box: Pubkey,
+    pub holder: Pubkey,
+    pub ticket_mint: Pubkey,
+    pub amount: u64,
+    pub currency: Option<Pubkey>, // None for sol
+}
+
+#[event]
+pub struct ProceedsWithdrawn {
+    pub ticket_box: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub currency: Option<Pubkey>, // None for sol
+}
+
+#[event]
+pub struct OperatorAdded {
+    pub ticket_box: Pubkey,
+    pub authority: Pubkey,
+    pub permissions: u8,
+}
+
+#[event]
+pub struct OperatorRemoved {
+    pub ticket_box: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::{Operator, OperatorAdded, TicketBox};
+
+#[derive(Accounts)]
+#[instruction( authority: Pubkey )]
+pub struct AddOperator<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [b"operator", ticket_box.key().as_ref(), authority.as_ref()],
+        bump,
+        space = Operator::SIZE
+    )]
+    pub operator: Account<'info, Operator>,
+
+    // native
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddOperator>, authority: Pubkey, permissions: u8) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !Operator::ALL_PERMISSIONS == 0,
+        ErrorCode::InvalidOperatorPermissions
+    );
+
+    // adding an existing operator replaces its permissions, and takes over one left
+    // by a closed box with the same uuid
+    let operator = &mut ctx.accounts.operator;
+    operator.ticket_box = ctx.accounts.ticket_box.key();
+    operator.collection_mint = ctx.accounts.ticket_box.collection_mint;
+    operator.authority = authority;
+    operator.permissions = permissions;
+
+    emit!(OperatorAdded {
+        ticket_box: operator.ticket_box,
+        authority,
+        permissions,
+    });
+
+    Ok(())
+}
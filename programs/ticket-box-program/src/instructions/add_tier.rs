@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::{validate_sale, validate_uri, TicketBox, TicketTier, TicketTierAdded};
+
+#[derive(Accounts)]
+pub struct AddTier<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        seeds = [b"ticket_tier", ticket_box.key().as_ref(), &[ticket_box.num_of_tiers]],
+        bump,
+        space = TicketTier::SIZE
+    )]
+    pub ticket_tier: Account<'info, TicketTier>,
+
+    // native
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<AddTier>,
+    name: String,
+    uri: String,
+    start_at: i64,
+    end_at: Option<i64>,
+    num_of_tickets: Option<i64>,
+    ticket_per_wallet: Option<i64>,
+    price: u64,
+) -> Result<()> {
+    let ticket_box = &mut ctx.accounts.ticket_box;
+    require!(
+        ticket_box.num_of_tiers < TicketBox::MAX_TIERS,
+        ErrorCode::TooManyTicketTiers
+    );
+
+    require!(
+        name.len() <= TicketBox::MAX_NAME_LEN,
+        ErrorCode::NameTooLong
+    );
+    validate_uri(&uri, ticket_box.uri_mode, ticket_box.num_of_tickets)?;
+
+    // only a tier left by a closed box with the same uuid can already be there
+    let ticket_tier = &mut ctx.accounts.ticket_tier;
+    require!(
+        ticket_tier.ticket_box == Pubkey::default()
+            || ticket_tier.collection_mint != ticket_box.collection_mint,
+        ErrorCode::InvalidRelatedAccount
+    );
+    ticket_tier.ticket_box = ticket_box.key();
+    ticket_tier.collection_mint = ticket_box.collection_mint;
+    ticket_tier.index = ticket_box.num_of_tiers;
+    ticket_tier.name = name;
+    ticket_tier.uri = uri;
+    ticket_tier.start_at = start_at;
+    ticket_tier.end_at = end_at;
+    ticket_tier.num_of_tickets = num_of_tickets;
+    ticket_tier.num_of_sold = 0;
+    ticket_tier.ticket_per_wallet = ticket_per_wallet;
+    ticket_tier.price = price;
+
+    let clock = Clock::get()?;
+    validate_sale(&ticket_tier.sale_config(), None, clock.unix_timestamp)?;
+
+    ticket_box.num_of_tiers += 1;
+
+    emit!(TicketTierAdded {
+        ticket_box: ticket_tier.ticket_box,
+        ticket_tier: ticket_tier.key(),
+        index: ticket_tier.index,
+        name: ticket_tier.name.clone(),
+        uri: ticket_tier.uri.clone(),
+        start_at,
+        end_at,
+        num_of_tickets,
+        ticket_per_wallet,
+        price,
+    });
+
+    Ok(())
+}
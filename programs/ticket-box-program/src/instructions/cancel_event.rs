@@ -0,0 +1,95 @@
+use {
+    anchor_lang::{
+        prelude::*,
+        solana_program::{account_info::next_account_info, program::invoke, system_instruction},
+    },
+    anchor_spl::token,
+};
+
+use crate::errors::ErrorCode;
+use crate::{assert_keys_equal, spl_token_transfer, TokenTransferParams};
+use crate::{TicketBox, TicketBoxCancelled};
+
+#[derive(Accounts)]
+pub struct CancelEvent<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    /// CHECK: system-owned PDA holding the box's SOL proceeds
+    #[account(
+        mut,
+        seeds = [b"vault", ticket_box.key().as_ref()],
+        bump = ticket_box.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    // native
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, token::Token>,
+    // remaining accounts
+    // creator_token_account_info, when an SPL box paid protocol fees
+    // vault_token_account_info
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, CancelEvent<'info>>) -> Result<()> {
+    let ticket_box = &mut ctx.accounts.ticket_box;
+    let creator = &ctx.accounts.creator;
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    require!(!ticket_box.cancelled, ErrorCode::EventCancelled);
+    require!(!ticket_box.withdrawn, ErrorCode::ProceedsAlreadyWithdrawn);
+
+    // after `end_at` the proceeds can already be withdrawn
+    if let Some(end_at) = ticket_box.end_at {
+        require!(end_at > current_timestamp, ErrorCode::EventEnded);
+    }
+
+    // refunds pay the full price, the treasury keeps its fees so the creator puts them back
+    if ticket_box.protocol_fees > 0 {
+        if ticket_box.currency.is_some() {
+            let creator_token_account_info = next_account_info(remaining_accounts)?;
+            let vault_token_account_info = next_account_info(remaining_accounts)?;
+            assert_keys_equal(vault_token_account_info.key, &ticket_box.escrow)?;
+
+            spl_token_transfer(TokenTransferParams {
+                source: creator_token_account_info.clone(),
+                destination: vault_token_account_info.clone(),
+                amount: ticket_box.protocol_fees,
+                authority: creator.to_account_info(),
+                authority_signer_seeds: &[],
+                token_program: ctx.accounts.token_program.to_account_info(),
+            })?;
+        } else {
+            invoke(
+                &system_instruction::transfer(
+                    creator.key,
+                    ctx.accounts.vault.key,
+                    ticket_box.protocol_fees,
+                ),
+                &[
+                    creator.to_account_info(),
+                    ctx.accounts.vault.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+            )?;
+        }
+    }
+
+    ticket_box.cancelled = true;
+
+    emit!(TicketBoxCancelled {
+        ticket_box: ticket_box.key(),
+        protocol_fees: ticket_box.protocol_fees,
+    });
+
+    Ok(())
+}
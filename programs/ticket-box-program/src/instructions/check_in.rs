@@ -0,0 +1,57 @@
+use {anchor_lang::prelude::*, anchor_spl::token};
+
+use crate::{assert_permission, errors::ErrorCode};
+use crate::{Operator, Ticket, TicketBox, TicketCheckedIn};
+
+#[derive(Accounts)]
+pub struct CheckIn<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), ticket_box.creator.as_ref()],
+        bump = ticket_box.bump
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    pub ticket_mint: Account<'info, token::Mint>,
+    #[account(
+        token::mint = ticket_mint,
+        constraint = ticket_token_account.amount == 1 @ ErrorCode::InvalidTicketTokenAccount,
+    )]
+    pub ticket_token_account: Account<'info, token::TokenAccount>,
+    #[account(
+        mut,
+        has_one = ticket_box,
+        constraint = ticket.collection_mint == ticket_box.collection_mint
+            @ ErrorCode::InvalidRelatedAccount,
+        seeds = [b"ticket", ticket_box.key().as_ref(), ticket_mint.key().as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, Ticket>,
+    // remaining accounts
+    // operator_info, when `authority` is not the creator
+}
+
+pub fn handler(ctx: Context<CheckIn>) -> Result<()> {
+    let ticket_box = &ctx.accounts.ticket_box;
+    let ticket = &mut ctx.accounts.ticket;
+
+    assert_permission(
+        ticket_box,
+        ctx.accounts.authority.key,
+        ctx.remaining_accounts.first(),
+        Operator::CHECK_IN,
+    )?;
+    require!(!ticket_box.cancelled, ErrorCode::EventCancelled);
+    require!(ticket.checked_in_at.is_none(), ErrorCode::AlreadyCheckedIn);
+
+    let clock = Clock::get()?;
+    ticket.checked_in_at = Some(clock.unix_timestamp);
+
+    emit!(TicketCheckedIn {
+        ticket_box: ticket_box.key(),
+        ticket_mint: ticket.mint,
+        authority: ctx.accounts.authority.key(),
+        checked_in_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use {
+    anchor_lang::{
+        prelude::*,
+        solana_program::{
+            account_info::next_account_info,
+            program::invoke_signed,
+            system_instruction,
+        },
+        AccountsClose,
+    },
+    anchor_spl::token,
+};
+
+use crate::{assert_initialized, assert_keys_equal, cmp_pubkeys, errors::ErrorCode};
+use crate::{Operator, PurchaseRecord, TicketBox, TicketBoxClosed, TicketTier};
+
+#[derive(Accounts)]
+pub struct CloseTicketBox<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    /// CHECK: system-owned PDA holding the box's SOL proceeds
+    #[account(
+        mut,
+        seeds = [b"vault", ticket_box.key().as_ref()],
+        bump = ticket_box.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    // native
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, token::Token>,
+    // remaining accounts
+    // vault_token_account_info, when the box sells in an SPL currency
+    // ticket tier and operator accounts of the box to close
+    // purchase record accounts of the box to close, each followed by its buyer
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, CloseTicketBox<'info>>) -> Result<()> {
+    let ticket_box = &ctx.accounts.ticket_box;
+    let vault = &ctx.accounts.vault;
+    let creator = &ctx.accounts.creator;
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    // an untouched box can always go, otherwise wait for the event to end
+    let ended = ticket_box.end_at.is_some() && ticket_box.end_at.unwrap() <= current_timestamp;
+    require!(
+        ticket_box.num_of_sold == 0 || ended,
+        ErrorCode::TicketBoxNotClosable
+    );
+
+    let ticket_box_key = ticket_box.key();
+    let signer_seeds = [
+        b"vault".as_ref(),
+        ticket_box_key.as_ref(),
+        &[ticket_box.vault_bump],
+    ];
+
+    if ticket_box.currency.is_some() {
+        let vault_token_account_info = next_account_info(remaining_accounts)?;
+
+        assert_keys_equal(vault_token_account_info.key, &ticket_box.escrow)?;
+        let vault_token_account: token::spl_token::state::Account =
+            assert_initialized(vault_token_account_info)?;
+        require!(
+            vault_token_account.amount == 0,
+            ErrorCode::EscrowNotWithdrawn
+        );
+
+        invoke_signed(
+            &token::spl_token::instruction::close_account(
+                ctx.accounts.token_program.key,
+                vault_token_account_info.key,
+                creator.key,
+                vault.key,
+                &[],
+            )?,
+            &[
+                vault_token_account_info.clone(),
+                creator.to_account_info(),
+                vault.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            ],
+            &[&signer_seeds],
+        )?;
+    } else {
+        let rent_exempt_lamports = Rent::get()?.minimum_balance(0);
+        require!(
+            ticket_box.num_of_sold == 0 || vault.lamports() <= rent_exempt_lamports,
+            ErrorCode::EscrowNotWithdrawn
+        );
+    }
+
+    // empty the vault, which garbage collects it
+    let vault_lamports = vault.lamports();
+    if vault_lamports > 0 {
+        invoke_signed(
+            &system_instruction::transfer(&vault.key(), &creator.key(), vault_lamports),
+            &[
+                vault.to_account_info(),
+                creator.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+            &[&signer_seeds],
+        )?;
+    }
+
+    while let Some(account_info) = remaining_accounts.next() {
+        close_related_account(
+            account_info,
+            remaining_accounts,
+            &ticket_box_key,
+            creator.to_account_info(),
+        )?;
+    }
+
+    emit!(TicketBoxClosed {
+        ticket_box: ticket_box_key,
+        creator: creator.key(),
+    });
+
+    Ok(())
+}
+
+fn close_related_account<'a, 'info: 'a>(
+    account_info: &AccountInfo<'info>,
+    remaining_accounts: &mut impl Iterator<Item = &'a AccountInfo<'info>>,
+    ticket_box: &Pubkey,
+    creator: AccountInfo<'info>,
+) -> Result<()> {
+    if let Ok(ticket_tier) = Account::<TicketTier>::try_from(account_info) {
+        require!(
+            cmp_pubkeys(&ticket_tier.ticket_box, ticket_box),
+            ErrorCode::InvalidRelatedAccount
+        );
+        return ticket_tier.close(creator);
+    }
+
+    if let Ok(purchase_record) = Account::<PurchaseRecord>::try_from(account_info) {
+        require!(
+            cmp_pubkeys(&purchase_record.ticket_box, ticket_box),
+            ErrorCode::InvalidRelatedAccount
+        );
+        // the buyer paid for the record, so the rent goes back to them
+        let buyer_info = next_account_info(remaining_accounts)?;
+        assert_keys_equal(buyer_info.key, &purchase_record.buyer)?;
+        return purchase_record.close(buyer_info.clone());
+    }
+
+    if let Ok(operator) = Account::<Operator>::try_from(account_info) {
+        require!(
+            cmp_pubkeys(&operator.ticket_box, ticket_box),
+            ErrorCode::InvalidRelatedAccount
+        );
+        return operator.close(creator);
+    }
+
+    err!(ErrorCode::InvalidRelatedAccount)
+}
@@ -1,31 +1,28 @@
 use {
-    anchor_lang::{prelude::*, solana_program::program::invoke_signed},
-    anchor_spl::token::{self},
-    mpl_token_metadata::{
-        instruction as mpl_instruction, state::CollectionDetails, utils::assert_owned_by,
-        ID as MPL_TOKEN_METADATA_ID,
-    },
+    anchor_lang::{prelude::*, solana_program::account_info::next_account_info},
+    anchor_spl::{associated_token, token},
 };
 
-use crate::TicketBox;
-use crate::{assert_initialized, cmp_pubkeys, errors::ErrorCode};
+use crate::{assert_keys_equal, create_collection, create_pda_account, CreateCollectionParams};
+use crate::{errors::ErrorCode, fund_vault, init_vault_token_account, validate_sale};
+use crate::{validate_naming, validate_royalties, validate_uri, InitVaultTokenAccountParams};
+use crate::{TicketBox, TicketBoxCreated, TicketBoxCreator, UriMode};
 
 #[derive(Accounts)]
-#[instruction( uuid: String )]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
+    /// CHECK: created in the handler, once the uuid is known to fit in its seeds
+    #[account(mut)]
+    pub ticket_box: UncheckedAccount<'info>,
+
+    /// CHECK: system-owned PDA holding the box's SOL proceeds
     #[account(
-        init,
-        payer = creator,
-        seeds = [b"ticket_box", uuid.as_bytes(), creator.key().as_ref()],
-        bump,
-        space = TicketBox::SIZE
+        mut,
+        seeds = [b"vault", ticket_box.key().as_ref()],
+        bump
     )]
-    pub ticket_box: Account<'info, TicketBox>,
-
-    /// CHECK: wallet can be any account and is not written to or read
-    pub wallet: UncheckedAccount<'info>,
+    pub vault: UncheckedAccount<'info>,
 
     //collection
     #[account(mut)]
@@ -44,12 +41,16 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
     pub token_program: Program<'info, token::Token>,
+    pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
     /// CHECK: Metaplex will check this
     pub token_metadata_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // currency_mint_info
+    // vault_token_account_info
 }
 
-pub fn handler(
-    ctx: Context<Initialize>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
     uuid: String,
     name: String,
     uri: String,
@@ -59,134 +60,156 @@ pub fn handler(
     ticket_per_wallet: Option<i64>,
     price: u64,
     transferable: bool,
+    seller_fee_basis_points: u16,
+    creators: Vec<TicketBoxCreator>,
+    uri_mode: UriMode,
+    symbol: String,
+    name_format: String,
 ) -> Result<()> {
-    let clock = Clock::get()?;
-    let current_timestamp = clock.unix_timestamp;
-
+    // before anything derives the box address, longer seeds abort the program
     require!(
-        start_at >= current_timestamp,
-        ErrorCode::InvalidTicketBoxStartAt
+        uuid.len() <= TicketBox::MAX_UUID_LEN,
+        ErrorCode::UuidTooLong
     );
+    require!(
+        name.len() <= TicketBox::MAX_NAME_LEN,
+        ErrorCode::NameTooLong
+    );
+    require!(uri.len() <= TicketBox::MAX_URI_LEN, ErrorCode::UriTooLong);
 
-    if end_at.is_some() {
-        require!(end_at.unwrap() > start_at, ErrorCode::InvalidTicketBoxEndAt);
-    }
+    let creator = &ctx.accounts.creator;
+    let ticket_box_info = ctx.accounts.ticket_box.to_account_info();
+    let vault = &ctx.accounts.vault;
 
-    if num_of_tickets.is_some() {
-        require!(
-            ticket_per_wallet.is_some(),
-            ErrorCode::InvalidTicketPerWallet
-        );
+    let (ticket_box_key, bump) = Pubkey::find_program_address(
+        &[b"ticket_box", uuid.as_bytes(), creator.key.as_ref()],
+        ctx.program_id,
+    );
+    assert_keys_equal(&ticket_box_key, ticket_box_info.key)?;
+
+    // royalties go to the box creator unless split
+    let creators = if creators.is_empty() {
+        vec![TicketBoxCreator {
+            address: creator.key(),
+            share: 100,
+        }]
+    } else {
+        creators
+    };
+    validate_royalties(seller_fee_basis_points, &creators)?;
+    validate_naming(&name, &symbol, &name_format, num_of_tickets)?;
+    validate_uri(&uri, uri_mode, num_of_tickets)?;
+
+    let mut ticket_box = TicketBox {
+        version: TicketBox::VERSION,
+        bump,
+        uuid: uuid.clone(),
+        creator: creator.key(),
+        name: name.clone(),
+        uri: uri.clone(),
+        start_at,
+        end_at,
+        num_of_tickets,
+        num_of_sold: 0,
+        ticket_per_wallet,
+        currency: None,
+        price,
+        transferable,
+        cancelled: false,
+        withdrawn: false,
+        protocol_fees: 0,
+        escrow: vault.key(),
+        vault_bump: *ctx.bumps.get("vault").unwrap(),
+        collection_mint: ctx.accounts.collection_mint.key(),
+        num_of_tiers: 0,
+        seller_fee_basis_points,
+        creators: creators.clone(),
+        uri_mode,
+        symbol: symbol.clone(),
+        name_format: name_format.clone(),
+    };
 
-        require!(
-            num_of_tickets.unwrap() > ticket_per_wallet.unwrap(),
-            ErrorCode::InvalidTicketPerWallet
-        );
-    }
+    let clock = Clock::get()?;
+    validate_sale(&ticket_box.sale_config(), None, clock.unix_timestamp)?;
+
+    // keep the vault rent exempt so it can receive any amount
+    fund_vault(
+        creator.to_account_info(),
+        vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+    )?;
 
-    ctx.accounts.ticket_box.creator = ctx.accounts.creator.key();
-    ctx.accounts.ticket_box.uuid = uuid.clone();
-    ctx.accounts.ticket_box.name = name.clone();
-    ctx.accounts.ticket_box.uri = uri.clone();
-    ctx.accounts.ticket_box.start_at = start_at;
-    ctx.accounts.ticket_box.end_at = end_at;
-    ctx.accounts.ticket_box.num_of_tickets = num_of_tickets;
-    ctx.accounts.ticket_box.num_of_sold = 0;
-    ctx.accounts.ticket_box.ticket_per_wallet = ticket_per_wallet;
-    ctx.accounts.ticket_box.price = price;
-    ctx.accounts.ticket_box.transferable = transferable;
-    ctx.accounts.ticket_box.escrow = ctx.accounts.wallet.key();
-
-    if !ctx.remaining_accounts.is_empty() {
-        let token_mint_info = &ctx.remaining_accounts[0];
-        let _token_mint: token::spl_token::state::Mint = assert_initialized(token_mint_info)?;
-        let token_account: token::spl_token::state::Account =
-            assert_initialized(&ctx.accounts.wallet)?;
-        assert_owned_by(token_mint_info, &token::spl_token::id())?;
-        assert_owned_by(&ctx.accounts.wallet, &token::spl_token::id())?;
-        if !cmp_pubkeys(&token_account.mint, token_mint_info.key) {
-            return err!(ErrorCode::MintMismatch);
-        }
-        ctx.accounts.ticket_box.currency = Some(*token_mint_info.key);
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    if let Some(token_mint_info) = remaining_accounts.next() {
+        let vault_token_account_info = next_account_info(remaining_accounts)?;
+        init_vault_token_account(InitVaultTokenAccountParams {
+            payer: creator.to_account_info(),
+            vault: vault.to_account_info(),
+            vault_token_account: vault_token_account_info.clone(),
+            currency_mint: token_mint_info.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        })?;
+
+        ticket_box.currency = Some(*token_mint_info.key);
+        ticket_box.escrow = vault_token_account_info.key();
     }
 
-    // create nft collection
-    // mint_collection(ctx, &uuid, &name, &uri)?;
-
-    Ok(())
-}
-
-pub fn mint_collection(
-    ctx: Context<Initialize>,
-    uuid: &str,
-    box_name: &str,
-    box_uri: &String,
-) -> Result<()> {
-    let bump = *ctx.bumps.get("ticket_box").unwrap();
-    let creator_key = ctx.accounts.creator.key();
-    let signer_seeds = [
-        b"ticket_box".as_ref(),
-        uuid.as_bytes().as_ref(),
-        creator_key.as_ref(),
-        &[bump],
-    ];
-
-    msg!("Creating metadata account...");
-    invoke_signed(
-        &mpl_instruction::create_metadata_accounts_v3(
-            MPL_TOKEN_METADATA_ID,
-            ctx.accounts.collection_metadata.key(),
-            ctx.accounts.collection_mint.key(),
-            creator_key,
-            creator_key,
-            ctx.accounts.ticket_box.key(),
-            box_name.to_string(),
-            String::from("BOX"),
-            box_uri.to_string(),
-            None,
-            200,
-            false,
-            true,
-            None,
-            None,
-            Some(CollectionDetails::V1 { size: 0 }),
-        ),
+    create_pda_account(
+        creator.to_account_info(),
+        ticket_box_info.clone(),
+        TicketBox::SIZE,
         &[
-            ctx.accounts.collection_metadata.to_account_info(),
-            ctx.accounts.collection_mint.to_account_info(),
-            ctx.accounts.creator.to_account_info(),
-            ctx.accounts.creator.to_account_info(),
-            ctx.accounts.ticket_box.to_account_info(),
-            ctx.accounts.rent.to_account_info(),
+            b"ticket_box",
+            uuid.as_bytes(),
+            creator.key.as_ref(),
+            &[bump],
         ],
-        &[&signer_seeds],
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
     )?;
+    {
+        let mut data = ticket_box_info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        ticket_box.try_serialize(&mut writer)?;
+    }
 
-    msg!("Creating master edition metadata account...");
-    invoke_signed(
-        &mpl_instruction::create_master_edition_v3(
-            MPL_TOKEN_METADATA_ID,
-            ctx.accounts.collection_master_edition.key(),
-            ctx.accounts.collection_mint.key(),
-            ctx.accounts.ticket_box.key(),
-            creator_key,
-            ctx.accounts.collection_metadata.key(),
-            creator_key,
-            Some(0), // max_supply: Option<u64>
-        ),
-        &[
-            ctx.accounts.collection_master_edition.to_account_info(),
-            ctx.accounts.collection_mint.to_account_info(),
-            ctx.accounts.ticket_box.to_account_info(),
-            ctx.accounts.creator.to_account_info(),
-            ctx.accounts.creator.to_account_info(),
-            ctx.accounts.collection_metadata.to_account_info(),
-            ctx.accounts.rent.to_account_info(),
-        ],
-        &[&signer_seeds],
-    )?;
+    emit!(TicketBoxCreated {
+        ticket_box: ticket_box_key,
+        creator: ticket_box.creator,
+        uuid,
+        name,
+        uri,
+        start_at,
+        end_at,
+        num_of_tickets,
+        ticket_per_wallet,
+        currency: ticket_box.currency,
+        price,
+        transferable,
+        collection_mint: ticket_box.collection_mint,
+        seller_fee_basis_points,
+        creators,
+        uri_mode,
+        symbol,
+        name_format,
+    });
 
-    msg!("Collection mint process completed successfully.");
+    // create nft collection
+    create_collection(CreateCollectionParams {
+        ticket_box: &ticket_box,
+        ticket_box_info,
+        creator: creator.to_account_info(),
+        collection_mint: ctx.accounts.collection_mint.to_account_info(),
+        collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+        collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    })?;
 
     Ok(())
 }
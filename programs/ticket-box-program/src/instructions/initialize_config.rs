@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::program::TicketBoxProgram;
+use crate::{Config, ConfigUpdated};
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"config"],
+        bump,
+        space = Config::SIZE
+    )]
+    pub config: Account<'info, Config>,
+
+    // only the upgrade authority of the program can set up the platform
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program: Program<'info, TicketBoxProgram>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ ErrorCode::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    // native
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<InitializeConfig>,
+    admin: Pubkey,
+    treasury: Pubkey,
+    fee_bps: u16,
+) -> Result<()> {
+    require!(fee_bps <= Config::MAX_FEE_BPS, ErrorCode::InvalidFeeBps);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = admin;
+    config.treasury = treasury;
+    config.fee_bps = fee_bps;
+    config.bump = *ctx.bumps.get("config").unwrap();
+
+    emit!(ConfigUpdated {
+        admin,
+        treasury,
+        fee_bps,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use {
+    anchor_lang::{
+        prelude::*,
+        solana_program::{account_info::next_account_info, program::invoke, system_instruction},
+        Discriminator,
+    },
+    anchor_spl::{associated_token, token},
+};
+
+use crate::{assert_keys_equal, cmp_pubkeys, errors::ErrorCode};
+use crate::{create_collection, format_ticket_name, validate_naming, CreateCollectionParams};
+use crate::{fund_vault, init_vault_token_account, InitVaultTokenAccountParams};
+use crate::{TicketBox, TicketBoxCreator, TicketBoxMigrated, TicketBoxV0, UriMode};
+
+#[derive(Accounts)]
+pub struct MigrateTicketBox<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: older layouts don't deserialize as `TicketBox`, checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub ticket_box: UncheckedAccount<'info>,
+    /// CHECK: system-owned PDA holding the box's SOL proceeds
+    #[account(
+        mut,
+        seeds = [b"vault", ticket_box.key().as_ref()],
+        bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    // collection, unversioned boxes never had one
+    #[account(mut)]
+    pub collection_mint: Signer<'info>,
+    /// CHECK: We're about to create this with Metaplex
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+    /// CHECK: We're about to create this with Metaplex
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    // native
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, token::Token>,
+    pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+    /// CHECK: Metaplex will check this
+    pub token_metadata_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // currency_mint_info, when the box sells in an SPL currency
+    // vault_token_account_info
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, MigrateTicketBox<'info>>) -> Result<()> {
+    let ticket_box_info = ctx.accounts.ticket_box.to_account_info();
+    let creator = &ctx.accounts.creator;
+
+    {
+        let data = ticket_box_info.try_borrow_data()?;
+        require!(
+            data.len() > 8 && data[..8] == TicketBox::discriminator(),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        // unversioned boxes have no version byte, only their allocated size tells them apart
+        require!(
+            data.len() == TicketBoxV0::SIZE,
+            ErrorCode::TicketBoxAlreadyMigrated
+        );
+    }
+
+    let ticket_box = migrate_v0(&ctx)?;
+
+    // resize the account to the current layout and settle the rent difference with the creator
+    let rent_exempt_lamports = ctx.accounts.rent.minimum_balance(TicketBox::SIZE);
+    let lamports = ticket_box_info.lamports();
+    if lamports < rent_exempt_lamports {
+        invoke(
+            &system_instruction::transfer(
+                creator.key,
+                ticket_box_info.key,
+                rent_exempt_lamports - lamports,
+            ),
+            &[
+                creator.to_account_info(),
+                ticket_box_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    } else {
+        let excess = lamports - rent_exempt_lamports;
+        **ticket_box_info.try_borrow_mut_lamports()? -= excess;
+        **creator.to_account_info().try_borrow_mut_lamports()? += excess;
+    }
+    ticket_box_info.realloc(TicketBox::SIZE, true)?;
+
+    {
+        let mut data = ticket_box_info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data;
+        ticket_box.try_serialize(&mut writer)?;
+    }
+
+    create_collection(CreateCollectionParams {
+        ticket_box: &ticket_box,
+        ticket_box_info: ticket_box_info.clone(),
+        creator: creator.to_account_info(),
+        collection_mint: ctx.accounts.collection_mint.to_account_info(),
+        collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+        collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    })?;
+
+    emit!(TicketBoxMigrated {
+        ticket_box: ticket_box_info.key(),
+        version: TicketBox::VERSION,
+    });
+
+    Ok(())
+}
+
+/// Versions an unversioned box and moves its proceeds to the vault, its collection is
+/// created by the caller once the box is written.
+fn migrate_v0<'info>(
+    ctx: &Context<'_, '_, '_, 'info, MigrateTicketBox<'info>>,
+) -> Result<TicketBox> {
+    let ticket_box_info = ctx.accounts.ticket_box.to_account_info();
+    let creator = &ctx.accounts.creator;
+    let vault = &ctx.accounts.vault;
+
+    let ticket_box_v0 = {
+        let data = ticket_box_info.try_borrow_data()?;
+        TicketBoxV0::deserialize(&mut &data[8..])
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?
+    };
+    require!(
+        cmp_pubkeys(&ticket_box_v0.creator, creator.key),
+        ErrorCode::Unauthorized
+    );
+
+    let (ticket_box_key, bump) = Pubkey::find_program_address(
+        &[
+            b"ticket_box",
+            ticket_box_v0.uuid.as_bytes(),
+            ticket_box_v0.creator.as_ref(),
+        ],
+        ctx.program_id,
+    );
+    assert_keys_equal(&ticket_box_key, ticket_box_info.key)?;
+
+    // unversioned boxes took up to 256 byte names and 1000 byte uris, more than Metaplex
+    // does: names are cut to leave room for the longest serial in the default ticket name
+    // format, a cut uri would be broken so it is left for `update` to set
+    let max_serial = ticket_box_v0.num_of_tickets.unwrap_or(u32::MAX as i64);
+    let serial_len = format_ticket_name(TicketBox::DEFAULT_NAME_FORMAT, "", max_serial).len();
+    let name = truncate(
+        ticket_box_v0.name,
+        TicketBox::MAX_NAME_LEN.saturating_sub(serial_len),
+    );
+    validate_naming(
+        &name,
+        TicketBox::DEFAULT_SYMBOL,
+        TicketBox::DEFAULT_NAME_FORMAT,
+        ticket_box_v0.num_of_tickets,
+    )?;
+    let uri = if ticket_box_v0.uri.len() <= TicketBox::MAX_URI_LEN {
+        ticket_box_v0.uri
+    } else {
+        String::new()
+    };
+
+    // proceeds go to the vault from now on
+    fund_vault(
+        creator.to_account_info(),
+        vault.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent,
+    )?;
+
+    let mut escrow = vault.key();
+    if let Some(currency) = ticket_box_v0.currency {
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let token_mint_info = next_account_info(remaining_accounts)?;
+        let vault_token_account_info = next_account_info(remaining_accounts)?;
+        assert_keys_equal(token_mint_info.key, &currency)?;
+        init_vault_token_account(InitVaultTokenAccountParams {
+            payer: creator.to_account_info(),
+            vault: vault.to_account_info(),
+            vault_token_account: vault_token_account_info.clone(),
+            currency_mint: token_mint_info.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        })?;
+        escrow = vault_token_account_info.key();
+    }
+
+    Ok(TicketBox {
+        version: TicketBox::VERSION,
+        bump,
+        uuid: ticket_box_v0.uuid,
+        creator: ticket_box_v0.creator,
+        name,
+        uri,
+        start_at: ticket_box_v0.start_at,
+        end_at: ticket_box_v0.end_at,
+        num_of_tickets: ticket_box_v0.num_of_tickets,
+        num_of_sold: ticket_box_v0.num_of_sold,
+        ticket_per_wallet: ticket_box_v0.ticket_per_wallet,
+        currency: ticket_box_v0.currency,
+        price: ticket_box_v0.price,
+        transferable: ticket_box_v0.transferable,
+        cancelled: false,
+        withdrawn: false,
+        // unversioned boxes paid no fees
+        protocol_fees: 0,
+        escrow,
+        vault_bump: *ctx.bumps.get("vault").unwrap(),
+        collection_mint: ctx.accounts.collection_mint.key(),
+        num_of_tiers: 0,
+        // matches what the existing NFTs were minted with
+        seller_fee_basis_points: TicketBox::DEFAULT_SELLER_FEE_BASIS_POINTS,
+        creators: vec![TicketBoxCreator {
+            address: ticket_box_v0.creator,
+            share: 100,
+        }],
+        uri_mode: UriMode::Shared,
+        symbol: TicketBox::DEFAULT_SYMBOL.to_string(),
+        name_format: TicketBox::DEFAULT_NAME_FORMAT.to_string(),
+    })
+}
+
+/// Longest prefix of `value` within `max_len` bytes.
+fn truncate(mut value: String, max_len: usize) -> String {
+    if value.len() > max_len {
+        let mut len = max_len;
+        while !value.is_char_boundary(len) {
+            len -= 1;
+        }
+        value.truncate(len);
+    }
+    value
+}
@@ -2,20 +2,22 @@ use {
     anchor_lang::{
         prelude::*,
         solana_program::{
+            account_info::next_account_info,
             program::{invoke, invoke_signed},
             system_instruction,
         },
     },
     anchor_spl::{associated_token, token},
     mpl_token_metadata::{
-        instruction as mpl_instruction, state::Collection,
+        instruction as mpl_instruction, state::Collection, utils::assert_owned_by,
         ID as MPL_TOKEN_METADATA_ID,
     },
     std::vec,
 };
 
-use crate::{assert_is_ata, errors::ErrorCode, TokenTransferParams};
-use crate::{spl_token_transfer, TicketBox};
+use crate::{assert_initialized, assert_is_ata, cmp_pubkeys, errors::ErrorCode, TokenTransferParams};
+use crate::{spl_token_transfer, Config, PurchaseRecord, Ticket, TicketBox, TicketMinted};
+use crate::{format_ticket_name, metadata_creators, TicketTier, UriMode};
 
 // use std::vec;
 // use anchor_lang::solana_program::program::invoke;
@@ -27,17 +29,39 @@ pub struct MintTicket<'info> {
     pub payer: Signer<'info>,
     #[account(
         mut,
+        has_one = collection_mint @ ErrorCode::CollectionMintMismatch,
         seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), ticket_box.creator.as_ref()],
-        bump
+        bump = ticket_box.bump
     )]
     pub ticket_box: Account<'info, TicketBox>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"purchase_record", ticket_box.key().as_ref(), payer.key().as_ref()],
+        bump,
+        space = PurchaseRecord::SIZE
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
 
-    /// CHECK: wallet can be any account and is not written to or read
-    #[account(mut)]
+    /// CHECK: checked against `ticket_box.escrow`
+    #[account(mut, address = ticket_box.escrow @ ErrorCode::InvalidEscrow)]
     pub wallet: UncheckedAccount<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: checked against `config.treasury`
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
     //ticket
     #[account(mut)]
     pub ticket_mint: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"ticket", ticket_box.key().as_ref(), ticket_mint.key().as_ref()],
+        bump,
+        space = Ticket::SIZE
+    )]
+    pub ticket: Account<'info, Ticket>,
     /// CHECK: We're about to create this with Anchor
     #[account(mut)]
     pub ticket_token_account: UncheckedAccount<'info>,
@@ -47,7 +71,7 @@ pub struct MintTicket<'info> {
     /// CHECK: We're about to create this with Metaplex
     #[account(mut)]
     pub ticket_master_edition: UncheckedAccount<'info>,
-    /// CHECK: We're about to create this with Metaplex
+    /// CHECK: checked against `ticket_box.collection_mint`
     pub collection_mint: UncheckedAccount<'info>,
     /// CHECK: We're about to create this with Metaplex
     #[account(mut)]
@@ -64,21 +88,51 @@ pub struct MintTicket<'info> {
     /// CHECK: Metaplex will check this
     pub token_metadata_program: UncheckedAccount<'info>,
     // remaining accounts
+    // ticket_tier_info, when the box has tiers
+    // creator_info, signing the uri when the box uri mode is `CreatorSigned`
     // token_account_info
     // transfer_authority_info
+    // treasury_token_account_info, when a fee is due
 }
 
 pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, MintTicket<'info>>,
-    _uri: String,
+    uri: String,
+    max_price: u64,
+    currency: Option<Pubkey>,
 ) -> Result<()> {
     let payer = &ctx.accounts.payer;
-    let ticket_box = &ctx.accounts.ticket_box;
+    let ticket_box = &mut ctx.accounts.ticket_box;
     let wallet = &ctx.accounts.wallet;
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    // boxes with tiers only sell through one of them
+    let mut ticket_tier = if ticket_box.num_of_tiers > 0 {
+        let ticket_tier_info = remaining_accounts
+            .next()
+            .ok_or(ErrorCode::TicketTierRequired)?;
+        let ticket_tier = Account::<TicketTier>::try_from(ticket_tier_info)?;
+        require!(
+            cmp_pubkeys(&ticket_tier.ticket_box, &ticket_box.key())
+                && cmp_pubkeys(&ticket_tier.collection_mint, &ticket_box.collection_mint)
+                && ticket_tier.index < ticket_box.num_of_tiers,
+            ErrorCode::TicketTierMismatch
+        );
+        Some(ticket_tier)
+    } else {
+        None
+    };
 
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp;
 
+    require!(!ticket_box.cancelled, ErrorCode::EventCancelled);
+
+    require!(
+        ticket_box.start_at <= current_timestamp,
+        ErrorCode::SaleNotStarted
+    );
+
     if ticket_box.end_at.is_some() {
         require!(
             ticket_box.end_at.unwrap() > current_timestamp,
@@ -93,13 +147,133 @@ pub fn handler<'info>(
         );
     }
 
+    if let Some(ticket_tier) = &mut ticket_tier {
+        require!(
+            ticket_tier.start_at <= current_timestamp,
+            ErrorCode::SaleNotStarted
+        );
+
+        if ticket_tier.end_at.is_some() {
+            require!(
+                ticket_tier.end_at.unwrap() > current_timestamp,
+                ErrorCode::TicketTierSaleEnded
+            );
+        }
+
+        if ticket_tier.num_of_tickets.is_some() {
+            require!(
+                ticket_tier.num_of_sold < ticket_tier.num_of_tickets.unwrap(),
+                ErrorCode::SoldOut
+            );
+        }
+
+        ticket_tier.num_of_sold = ticket_tier
+            .num_of_sold
+            .checked_add(1)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+    }
+
+    // serials run across all tiers of the box
+    let serial = ticket_box
+        .num_of_sold
+        .checked_add(1)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+    ticket_box.num_of_sold = serial;
+
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    // a record left by a closed box with the same uuid counts from zero
+    if !cmp_pubkeys(&purchase_record.collection_mint, &ticket_box.collection_mint) {
+        purchase_record.num_of_minted = 0;
+        purchase_record.tier_num_of_minted = [0; TicketBox::MAX_TIERS as usize];
+        purchase_record.collection_mint = ticket_box.collection_mint;
+    }
+    if ticket_box.ticket_per_wallet.is_some() {
+        require!(
+            purchase_record.num_of_minted < ticket_box.ticket_per_wallet.unwrap(),
+            ErrorCode::ExceededTicketPerWallet
+        );
+    }
+    purchase_record.ticket_box = ticket_box.key();
+    purchase_record.buyer = payer.key();
+    purchase_record.num_of_minted = purchase_record
+        .num_of_minted
+        .checked_add(1)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    if let Some(ticket_tier) = &ticket_tier {
+        let tier_num_of_minted =
+            &mut purchase_record.tier_num_of_minted[ticket_tier.index as usize];
+        if ticket_tier.ticket_per_wallet.is_some() {
+            require!(
+                *tier_num_of_minted < ticket_tier.ticket_per_wallet.unwrap(),
+                ErrorCode::ExceededTicketPerWallet
+            );
+        }
+        *tier_num_of_minted = tier_num_of_minted
+            .checked_add(1)
+            .ok_or(ErrorCode::NumericalOverflow)?;
+    }
+
     // transfer fee
-    let price = ticket_box.price;
+    let (price, shared_uri) = match &ticket_tier {
+        Some(ticket_tier) => (ticket_tier.price, ticket_tier.uri.clone()),
+        None => (ticket_box.price, ticket_box.uri.clone()),
+    };
+
+    // buyers only choose the uri with the creator's approval
+    let uri = match ticket_box.uri_mode {
+        UriMode::Shared => shared_uri,
+        UriMode::Template => shared_uri.replace(TicketBox::SERIAL_PLACEHOLDER, &serial.to_string()),
+        UriMode::CreatorSigned => {
+            let creator_info = remaining_accounts
+                .next()
+                .ok_or(ErrorCode::CreatorSignatureRequired)?;
+            require!(
+                creator_info.is_signer && cmp_pubkeys(creator_info.key, &ticket_box.creator),
+                ErrorCode::CreatorSignatureRequired
+            );
+            uri
+        }
+    };
+    require!(uri.len() <= TicketBox::MAX_URI_LEN, ErrorCode::UriTooLong);
+
+    // the buyer signed for at most this price in this currency
+    require!(price <= max_price, ErrorCode::PriceExceedsMaxPrice);
+    require!(
+        ticket_box.currency == currency,
+        ErrorCode::CurrencyMismatch
+    );
+
+    // the treasury takes its cut, the rest goes to the box escrow
+    let protocol_fee = ctx.accounts.config.protocol_fee(price);
+    let escrow_amount = price - protocol_fee;
+    // refunds pay the full price back, the creator covers these if the event is cancelled
+    ticket_box.protocol_fees = ticket_box
+        .protocol_fees
+        .checked_add(protocol_fee)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    let ticket = &mut ctx.accounts.ticket;
+    ticket.ticket_box = ticket_box.key();
+    ticket.mint = ctx.accounts.ticket_mint.key();
+    ticket.serial = serial;
+    ticket.price = price;
+    ticket.checked_in_at = None;
+    ticket.tier = ticket_tier.as_ref().map(|ticket_tier| ticket_tier.index);
+    ticket.protocol_fee = protocol_fee;
+    ticket.collection_mint = ticket_box.collection_mint;
+
     if price > 0 {
         if let Some(mint) = ticket_box.currency {
-            // TODO validate token account
-            let token_account_info = &ctx.remaining_accounts[0];
-            let transfer_authority_info = &ctx.remaining_accounts[1];
+            let wallet_token_account: token::spl_token::state::Account =
+                assert_initialized(wallet)?;
+            assert_owned_by(wallet, &token::spl_token::id())?;
+            if !cmp_pubkeys(&wallet_token_account.mint, &mint) {
+                return err!(ErrorCode::MintMismatch);
+            }
+
+            let token_account_info = next_account_info(remaining_accounts)?;
+            let transfer_authority_info = next_account_info(remaining_accounts)?;
 
             let token_account = assert_is_ata(token_account_info, &payer.key(), &mint)?;
             if token_account.amount < price {
@@ -108,33 +282,85 @@ pub fn handler<'info>(
             spl_token_transfer(TokenTransferParams {
                 source: token_account_info.clone(),
                 destination: wallet.to_account_info(),
-                amount: price,
+                amount: escrow_amount,
                 authority: transfer_authority_info.to_account_info(),
                 authority_signer_seeds: &[],
                 token_program: ctx.accounts.token_program.to_account_info(),
             })?;
+
+            if protocol_fee > 0 {
+                let treasury_token_account_info = next_account_info(remaining_accounts)?;
+                assert_is_ata(
+                    treasury_token_account_info,
+                    &ctx.accounts.config.treasury,
+                    &mint,
+                )?;
+                spl_token_transfer(TokenTransferParams {
+                    source: token_account_info.clone(),
+                    destination: treasury_token_account_info.clone(),
+                    amount: protocol_fee,
+                    authority: transfer_authority_info.to_account_info(),
+                    authority_signer_seeds: &[],
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                })?;
+            }
         } else {
             if ctx.accounts.payer.lamports() < price {
                 return err!(ErrorCode::NotEnoughSOL);
             }
             // transfer sol
             invoke(
-                &system_instruction::transfer(&ctx.accounts.payer.key(), &wallet.key(), price),
+                &system_instruction::transfer(
+                    &ctx.accounts.payer.key(),
+                    &wallet.key(),
+                    escrow_amount,
+                ),
                 &[
                     ctx.accounts.payer.to_account_info(),
                     wallet.to_account_info(),
                     ctx.accounts.system_program.to_account_info(),
                 ],
             )?;
+
+            if protocol_fee > 0 {
+                invoke(
+                    &system_instruction::transfer(
+                        &ctx.accounts.payer.key(),
+                        &ctx.accounts.treasury.key(),
+                        protocol_fee,
+                    ),
+                    &[
+                        ctx.accounts.payer.to_account_info(),
+                        ctx.accounts.treasury.to_account_info(),
+                        ctx.accounts.system_program.to_account_info(),
+                    ],
+                )?;
+            }
         }
     }
 
-    mint_ticket(ctx)?;
+    emit!(TicketMinted {
+        ticket_box: ticket_box.key(),
+        buyer: payer.key(),
+        ticket_mint: ctx.accounts.ticket_mint.key(),
+        serial,
+        tier: ticket.tier,
+        price,
+        protocol_fee,
+        currency: ticket_box.currency,
+    });
+
+    // the tier is a remaining account, so persist it by hand
+    if let Some(ticket_tier) = &ticket_tier {
+        ticket_tier.exit(ctx.program_id)?;
+    }
+
+    mint_ticket(ctx, serial, uri)?;
 
     Ok(())
 }
 
-fn mint_ticket(ctx: Context<MintTicket>) -> Result<()> {
+fn mint_ticket(ctx: Context<MintTicket>, serial: i64, uri: String) -> Result<()> {
     // mint ticket
     // let creators = vec![mpl_token_metadata::state::Creator {
     //     address: ctx.accounts.payer.key(),
@@ -142,20 +368,21 @@ fn mint_ticket(ctx: Context<MintTicket>) -> Result<()> {
     //     share: 100,
     // }];
 
-    let bump = *ctx.bumps.get("ticket_box").unwrap();
     let ticket_box = &ctx.accounts.ticket_box;
     // let ticket_box_key = ticket_box.key();
     let signer_seeds = [
         b"ticket_box".as_ref(),
         ticket_box.uuid.as_ref(),
         ticket_box.creator.as_ref(),
-        &[bump],
+        &[ticket_box.bump],
     ];
 
     msg!("Creating metadata account...");
-    let mut nft_name = String::from(&ticket_box.name);
-    nft_name.push_str(&(ticket_box.num_of_sold + 1).to_string());
-    msg!("check nft name {}", nft_name);
+    let nft_name = format_ticket_name(&ticket_box.name_format, &ticket_box.name, serial);
+    require!(
+        nft_name.len() <= TicketBox::MAX_NAME_LEN,
+        ErrorCode::NameFormatTooLong
+    );
     invoke_signed(
         &mpl_instruction::create_metadata_accounts_v3(
             MPL_TOKEN_METADATA_ID,
@@ -165,11 +392,11 @@ fn mint_ticket(ctx: Context<MintTicket>) -> Result<()> {
             ctx.accounts.payer.key(),
             ticket_box.key(),
             nft_name,
-            String::from("TICKET"),
-            ctx.accounts.ticket_box.uri.clone(),
-            None,
-            200,
-            false,
+            ticket_box.symbol.clone(),
+            uri,
+            Some(metadata_creators(&ticket_box.key(), &ticket_box.creators)),
+            ticket_box.seller_fee_basis_points,
+            true,
             true,
             Some(Collection {
                 verified: false,
@@ -236,6 +463,44 @@ fn mint_ticket(ctx: Context<MintTicket>) -> Result<()> {
         &[&signer_seeds],
     )?;
 
+    if !ticket_box.transferable {
+        msg!("Freezing non-transferable ticket...");
+        invoke(
+            &token::spl_token::instruction::approve(
+                ctx.accounts.token_program.key,
+                ctx.accounts.ticket_token_account.key,
+                &ticket_box.key(),
+                ctx.accounts.payer.key,
+                &[],
+                1,
+            )?,
+            &[
+                ctx.accounts.ticket_token_account.to_account_info(),
+                ctx.accounts.ticket_box.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            ],
+        )?;
+
+        invoke_signed(
+            &mpl_instruction::freeze_delegated_account(
+                MPL_TOKEN_METADATA_ID,
+                ticket_box.key(),
+                ctx.accounts.ticket_token_account.key(),
+                ctx.accounts.ticket_master_edition.key(),
+                ctx.accounts.ticket_mint.key(),
+            ),
+            &[
+                ctx.accounts.ticket_box.to_account_info(),
+                ctx.accounts.ticket_token_account.to_account_info(),
+                ctx.accounts.ticket_master_edition.to_account_info(),
+                ctx.accounts.ticket_mint.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            ],
+            &[&signer_seeds],
+        )?;
+    }
+
     msg!("Token mint process completed successfully.");
 
     Ok(())
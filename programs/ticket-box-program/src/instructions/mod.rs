@@ -6,3 +6,45 @@ pub use update::*;
 
 pub mod mint;
 pub use mint::*;
+
+pub mod withdraw;
+pub use withdraw::*;
+
+pub mod cancel_event;
+pub use cancel_event::*;
+
+pub mod refund;
+pub use refund::*;
+
+pub mod check_in;
+pub use check_in::*;
+
+pub mod add_operator;
+pub use add_operator::*;
+
+pub mod remove_operator;
+pub use remove_operator::*;
+
+pub mod thaw_ticket;
+pub use thaw_ticket::*;
+
+pub mod add_tier;
+pub use add_tier::*;
+
+pub mod update_tier;
+pub use update_tier::*;
+
+pub mod close_ticket_box;
+pub use close_ticket_box::*;
+
+pub mod migrate_ticket_box;
+pub use migrate_ticket_box::*;
+
+pub mod initialize_config;
+pub use initialize_config::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod update_ticket_metadata;
+pub use update_ticket_metadata::*;
@@ -0,0 +1,176 @@
+use {
+    anchor_lang::{
+        prelude::*,
+        solana_program::{
+            account_info::next_account_info,
+            program::{invoke, invoke_signed},
+            system_instruction,
+        },
+    },
+    anchor_spl::token,
+    mpl_token_metadata::{
+        instruction as mpl_instruction, utils::assert_owned_by, ID as MPL_TOKEN_METADATA_ID,
+    },
+};
+
+use crate::{assert_initialized, assert_keys_equal, cmp_pubkeys, errors::ErrorCode};
+use crate::{spl_token_transfer, Ticket, TicketBox, TicketRefunded, TokenTransferParams};
+use crate::{thaw_ticket_account, ThawTicketParams};
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), ticket_box.creator.as_ref()],
+        bump = ticket_box.bump
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    #[account(mut)]
+    pub ticket_mint: Account<'info, token::Mint>,
+    #[account(
+        mut,
+        token::mint = ticket_mint,
+        token::authority = holder,
+    )]
+    pub ticket_token_account: Account<'info, token::TokenAccount>,
+    #[account(
+        mut,
+        close = holder,
+        has_one = ticket_box,
+        constraint = ticket.collection_mint == ticket_box.collection_mint
+            @ ErrorCode::InvalidRelatedAccount,
+        seeds = [b"ticket", ticket_box.key().as_ref(), ticket_mint.key().as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, Ticket>,
+    /// CHECK: Metaplex will check this
+    #[account(mut)]
+    pub ticket_metadata: UncheckedAccount<'info>,
+    /// CHECK: Metaplex will check this
+    #[account(mut)]
+    pub ticket_master_edition: UncheckedAccount<'info>,
+    /// CHECK: Metaplex checks the ticket is a verified member
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+    /// CHECK: system-owned PDA holding the box's SOL proceeds
+    #[account(
+        mut,
+        seeds = [b"vault", ticket_box.key().as_ref()],
+        bump = ticket_box.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    // native
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, token::Token>,
+    /// CHECK: checked against the Metaplex program id
+    #[account(address = MPL_TOKEN_METADATA_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // vault_token_account_info
+    // holder_token_account_info
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+    let ticket_box = &mut ctx.accounts.ticket_box;
+    let vault = &ctx.accounts.vault;
+    let holder = &ctx.accounts.holder;
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    require!(ticket_box.cancelled, ErrorCode::EventNotCancelled);
+
+    // non-transferable tickets are frozen since mint
+    if ctx.accounts.ticket_token_account.is_frozen() {
+        thaw_ticket_account(ThawTicketParams {
+            ticket_box,
+            ticket_token_account: ctx.accounts.ticket_token_account.to_account_info(),
+            ticket_master_edition: ctx.accounts.ticket_master_edition.to_account_info(),
+            ticket_mint: ctx.accounts.ticket_mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        })?;
+    }
+
+    // burn the ticket through Metaplex, which also closes its token, metadata and edition
+    // accounts and returns their rent to the holder
+    invoke(
+        &mpl_instruction::burn_nft(
+            MPL_TOKEN_METADATA_ID,
+            ctx.accounts.ticket_metadata.key(),
+            holder.key(),
+            ctx.accounts.ticket_mint.key(),
+            ctx.accounts.ticket_token_account.key(),
+            ctx.accounts.ticket_master_edition.key(),
+            ctx.accounts.token_program.key(),
+            Some(ctx.accounts.collection_metadata.key()),
+        ),
+        &[
+            ctx.accounts.ticket_metadata.to_account_info(),
+            holder.to_account_info(),
+            ctx.accounts.ticket_mint.to_account_info(),
+            ctx.accounts.ticket_token_account.to_account_info(),
+            ctx.accounts.ticket_master_edition.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.collection_metadata.to_account_info(),
+        ],
+    )?;
+
+    ticket_box.num_of_sold = ticket_box
+        .num_of_sold
+        .checked_sub(1)
+        .ok_or(ErrorCode::NumericalOverflow)?;
+
+    // pay back the full price, `cancel_event` put the protocol fees back in the vault
+    let amount = ctx.accounts.ticket.price;
+    if amount > 0 {
+        let ticket_box_key = ticket_box.key();
+        let signer_seeds = [
+            b"vault".as_ref(),
+            ticket_box_key.as_ref(),
+            &[ticket_box.vault_bump],
+        ];
+
+        if let Some(mint) = ticket_box.currency {
+            let vault_token_account_info = next_account_info(remaining_accounts)?;
+            let holder_token_account_info = next_account_info(remaining_accounts)?;
+
+            assert_keys_equal(vault_token_account_info.key, &ticket_box.escrow)?;
+            let holder_token_account: token::spl_token::state::Account =
+                assert_initialized(holder_token_account_info)?;
+            assert_owned_by(holder_token_account_info, &token::spl_token::id())?;
+            if !cmp_pubkeys(&holder_token_account.mint, &mint) {
+                return err!(ErrorCode::MintMismatch);
+            }
+
+            spl_token_transfer(TokenTransferParams {
+                source: vault_token_account_info.clone(),
+                destination: holder_token_account_info.clone(),
+                amount,
+                authority: vault.to_account_info(),
+                authority_signer_seeds: &signer_seeds,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            })?;
+        } else {
+            invoke_signed(
+                &system_instruction::transfer(&vault.key(), &holder.key(), amount),
+                &[
+                    vault.to_account_info(),
+                    holder.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+                &[&signer_seeds],
+            )?;
+        }
+    }
+
+    emit!(TicketRefunded {
+        ticket_box: ticket_box.key(),
+        holder: holder.key(),
+        ticket_mint: ctx.accounts.ticket_mint.key(),
+        amount,
+        currency: ticket_box.currency,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{Operator, OperatorRemoved, TicketBox};
+
+#[derive(Accounts)]
+pub struct RemoveOperator<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    #[account(
+        mut,
+        close = creator,
+        has_one = ticket_box,
+        seeds = [b"operator", ticket_box.key().as_ref(), operator.authority.as_ref()],
+        bump,
+    )]
+    pub operator: Account<'info, Operator>,
+}
+
+pub fn handler(ctx: Context<RemoveOperator>) -> Result<()> {
+    emit!(OperatorRemoved {
+        ticket_box: ctx.accounts.ticket_box.key(),
+        authority: ctx.accounts.operator.authority,
+    });
+
+    Ok(())
+}
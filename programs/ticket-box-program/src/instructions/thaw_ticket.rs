@@ -0,0 +1,64 @@
+use {anchor_lang::prelude::*, anchor_spl::token};
+
+use crate::errors::ErrorCode;
+use crate::{thaw_ticket_account, ThawTicketParams, Ticket, TicketBox, TicketThawed};
+
+#[derive(Accounts)]
+pub struct ThawTicket<'info> {
+    pub holder: Signer<'info>,
+    #[account(
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), ticket_box.creator.as_ref()],
+        bump = ticket_box.bump
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    pub ticket_mint: Account<'info, token::Mint>,
+    #[account(
+        mut,
+        token::mint = ticket_mint,
+        token::authority = holder,
+    )]
+    pub ticket_token_account: Account<'info, token::TokenAccount>,
+    #[account(
+        has_one = ticket_box,
+        constraint = ticket.collection_mint == ticket_box.collection_mint
+            @ ErrorCode::InvalidRelatedAccount,
+        seeds = [b"ticket", ticket_box.key().as_ref(), ticket_mint.key().as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, Ticket>,
+    /// CHECK: Metaplex will check this
+    pub ticket_master_edition: UncheckedAccount<'info>,
+
+    // native
+    pub token_program: Program<'info, token::Token>,
+    /// CHECK: Metaplex will check this
+    pub token_metadata_program: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ThawTicket>) -> Result<()> {
+    let ticket_box = &ctx.accounts.ticket_box;
+
+    // tickets only unlock once used, refundable, or the box became transferable
+    require!(
+        ctx.accounts.ticket.checked_in_at.is_some()
+            || ticket_box.cancelled
+            || ticket_box.transferable,
+        ErrorCode::ThawNotAllowed
+    );
+
+    thaw_ticket_account(ThawTicketParams {
+        ticket_box,
+        ticket_token_account: ctx.accounts.ticket_token_account.to_account_info(),
+        ticket_master_edition: ctx.accounts.ticket_master_edition.to_account_info(),
+        ticket_mint: ctx.accounts.ticket_mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    })?;
+
+    emit!(TicketThawed {
+        ticket_box: ticket_box.key(),
+        ticket_mint: ctx.accounts.ticket_mint.key(),
+        holder: ctx.accounts.holder.key(),
+    });
+
+    Ok(())
+}
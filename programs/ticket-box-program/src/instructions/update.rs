@@ -1,97 +1,307 @@
-use anchor_lang::prelude::*;
+use {
+    anchor_lang::{prelude::*, solana_program::program::invoke_signed},
+    anchor_spl::{associated_token, token},
+    mpl_token_metadata::ID as MPL_TOKEN_METADATA_ID,
+};
 
 use crate::errors::ErrorCode;
-use crate::TicketBox;
+use crate::{assert_initialized, assert_keys_equal, assert_permission, cmp_pubkeys};
+use crate::{init_vault_token_account, validate_sale};
+use crate::{update_metadata, UpdateMetadataParams};
+use crate::{validate_naming, validate_royalties, validate_uri};
+use crate::{CurrencyUpdate, InitVaultTokenAccountParams};
+use crate::{Operator, OptionUpdate, TicketBox, TicketBoxCreator, TicketBoxUpdated, UriMode};
 
 #[derive(Accounts)]
 pub struct UpdateTicketBox<'info> {
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub authority: Signer<'info>,
     #[account(
-       mut,
-       has_one = creator,
-        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
-        bump,
+        mut,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), ticket_box.creator.as_ref()],
+        bump = ticket_box.bump,
     )]
     pub ticket_box: Account<'info, TicketBox>,
+    /// CHECK: system-owned PDA holding the box's SOL proceeds
+    #[account(
+        seeds = [b"vault", ticket_box.key().as_ref()],
+        bump = ticket_box.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: metadata of `ticket_box.collection_mint`, updated by Metaplex
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            token_metadata_program.key().as_ref(),
+            ticket_box.collection_mint.as_ref()
+        ],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub collection_metadata: UncheckedAccount<'info>,
     // native
-    // pub system_program: Program<'info, System>,
-    // pub rent: Sysvar<'info, Rent>,
-    // pub token_program: Program<'info, token::Token>,
-    // pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, token::Token>,
+    pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+    /// CHECK: checked against the Metaplex program id
+    #[account(address = MPL_TOKEN_METADATA_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    // remaining accounts
+    // operator_info, when `authority` is not the creator
+    // currency_mint_info, when switching to a token currency
+    // vault_token_account_info, when switching to a token currency
+    // previous_vault_token_account_info, when switching away from a token currency
 }
 
-pub fn handler(
-    ctx: Context<UpdateTicketBox>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateTicketBox<'info>>,
     name: Option<String>,
     uri: Option<String>,
     start_at: Option<i64>,
-    end_at: Option<i64>,
-    num_of_tickets: Option<i64>,
-    ticket_per_wallet: Option<i64>,
+    end_at: OptionUpdate,
+    num_of_tickets: OptionUpdate,
+    ticket_per_wallet: OptionUpdate,
     price: Option<u64>,
     transferable: Option<bool>,
+    currency: CurrencyUpdate,
+    seller_fee_basis_points: Option<u16>,
+    creators: Option<Vec<TicketBoxCreator>>,
+    uri_mode: Option<UriMode>,
+    symbol: Option<String>,
+    name_format: Option<String>,
 ) -> Result<()> {
+    // operators can only update the box metadata
+    if !cmp_pubkeys(ctx.accounts.authority.key, &ctx.accounts.ticket_box.creator) {
+        require!(
+            start_at.is_none()
+                && end_at == OptionUpdate::Keep
+                && num_of_tickets == OptionUpdate::Keep
+                && ticket_per_wallet == OptionUpdate::Keep
+                && price.is_none()
+                && transferable.is_none()
+                && currency == CurrencyUpdate::Keep
+                && seller_fee_basis_points.is_none()
+                && creators.is_none()
+                && uri_mode.is_none(),
+            ErrorCode::Unauthorized
+        );
+    }
+    assert_permission(
+        &ctx.accounts.ticket_box,
+        ctx.accounts.authority.key,
+        ctx.remaining_accounts.first(),
+        Operator::UPDATE_METADATA,
+    )?;
+
     if name.is_some() {
-        ctx.accounts.ticket_box.name = name.unwrap();
+        require!(
+            name.as_ref().unwrap().len() <= TicketBox::MAX_NAME_LEN,
+            ErrorCode::NameTooLong
+        );
+        ctx.accounts.ticket_box.name = name.clone().unwrap();
     }
 
     if uri.is_some() {
-        ctx.accounts.ticket_box.uri = uri.unwrap();
+        require!(
+            uri.as_ref().unwrap().len() <= TicketBox::MAX_URI_LEN,
+            ErrorCode::UriTooLong
+        );
+        ctx.accounts.ticket_box.uri = uri.clone().unwrap();
     }
 
+    let previous_sale = ctx.accounts.ticket_box.sale_config();
+
     if start_at.is_some() {
-        let start_at = start_at.unwrap();
+        ctx.accounts.ticket_box.start_at = start_at.unwrap();
+    }
 
-        let clock = Clock::get()?;
-        let current_timestamp = clock.unix_timestamp;
+    // clearing a limit makes it unlimited
+    let ticket_box = &mut ctx.accounts.ticket_box;
+    ticket_box.end_at = end_at.apply(ticket_box.end_at);
+    ticket_box.num_of_tickets = num_of_tickets.apply(ticket_box.num_of_tickets);
+    ticket_box.ticket_per_wallet = ticket_per_wallet.apply(ticket_box.ticket_per_wallet);
 
-        require!(
-            start_at >= current_timestamp,
-            ErrorCode::InvalidTicketBoxStartAt
-        );
-        ctx.accounts.ticket_box.start_at = start_at;
+    if price.is_some() {
+        ctx.accounts.ticket_box.price = price.unwrap();
     }
 
-    if end_at.is_some() {
-        let end_at = end_at.unwrap();
-        require!(
-            end_at > ctx.accounts.ticket_box.start_at,
-            ErrorCode::InvalidTicketBoxEndAt
-        );
-        ctx.accounts.ticket_box.end_at = Some(end_at);
+    if transferable.is_some() {
+        ctx.accounts.ticket_box.transferable = transferable.unwrap();
     }
 
-    if num_of_tickets.is_some() {
-        let num_of_tickets = num_of_tickets.unwrap();
+    if currency != CurrencyUpdate::Keep {
+        // buyers paid in the current currency, refunds and withdrawals depend on it
         require!(
-            ticket_per_wallet.is_some(),
-            ErrorCode::InvalidTicketPerWallet
+            ctx.accounts.ticket_box.num_of_sold == 0,
+            ErrorCode::CurrencyLocked
         );
 
-        let ticket_per_wallet = ticket_per_wallet.unwrap();
-        require!(
-            num_of_tickets > ticket_per_wallet,
-            ErrorCode::InvalidTicketPerWallet
-        );
+        let previous_currency = ctx.accounts.ticket_box.currency;
+        let previous_escrow = ctx.accounts.ticket_box.escrow;
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+        match currency {
+            CurrencyUpdate::Token { mint } => {
+                let token_mint_info = remaining_accounts
+                    .next()
+                    .ok_or(ErrorCode::MintMismatch)?;
+                let vault_token_account_info = remaining_accounts
+                    .next()
+                    .ok_or(ErrorCode::InvalidEscrow)?;
+                require!(
+                    cmp_pubkeys(token_mint_info.key, &mint),
+                    ErrorCode::MintMismatch
+                );
+                init_vault_token_account(InitVaultTokenAccountParams {
+                    payer: ctx.accounts.authority.to_account_info(),
+                    vault: ctx.accounts.vault.to_account_info(),
+                    vault_token_account: vault_token_account_info.clone(),
+                    currency_mint: token_mint_info.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    associated_token_program: ctx
+                        .accounts
+                        .associated_token_program
+                        .to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                })?;
+
+                ctx.accounts.ticket_box.currency = Some(mint);
+                ctx.accounts.ticket_box.escrow = vault_token_account_info.key();
+            }
+            CurrencyUpdate::Sol => {
+                ctx.accounts.ticket_box.currency = None;
+                ctx.accounts.ticket_box.escrow = ctx.accounts.vault.key();
+            }
+            CurrencyUpdate::Keep => {}
+        }
 
-        ctx.accounts.ticket_box.num_of_tickets = Some(num_of_tickets);
-        ctx.accounts.ticket_box.ticket_per_wallet = Some(ticket_per_wallet);
+        // the previous vault token account is left empty, give its rent back
+        if previous_currency.is_some() && previous_escrow != ctx.accounts.ticket_box.escrow {
+            let previous_vault_token_account_info = remaining_accounts
+                .next()
+                .ok_or(ErrorCode::InvalidEscrow)?;
+            assert_keys_equal(previous_vault_token_account_info.key, &previous_escrow)?;
+            let previous_vault_token_account: token::spl_token::state::Account =
+                assert_initialized(previous_vault_token_account_info)?;
+            require!(
+                previous_vault_token_account.amount == 0,
+                ErrorCode::EscrowNotWithdrawn
+            );
+
+            let ticket_box_key = ctx.accounts.ticket_box.key();
+            let signer_seeds = [
+                b"vault".as_ref(),
+                ticket_box_key.as_ref(),
+                &[ctx.accounts.ticket_box.vault_bump],
+            ];
+            invoke_signed(
+                &token::spl_token::instruction::close_account(
+                    ctx.accounts.token_program.key,
+                    previous_vault_token_account_info.key,
+                    ctx.accounts.authority.key,
+                    ctx.accounts.vault.key,
+                    &[],
+                )?,
+                &[
+                    previous_vault_token_account_info.clone(),
+                    ctx.accounts.authority.to_account_info(),
+                    ctx.accounts.vault.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                ],
+                &[&signer_seeds],
+            )?;
+        }
     }
 
-    if transferable.is_some() {
-        ctx.accounts.ticket_box.transferable = transferable.unwrap();
+    // applies to tickets minted from now on
+    if seller_fee_basis_points.is_some() || creators.is_some() {
+        let ticket_box = &mut ctx.accounts.ticket_box;
+        if seller_fee_basis_points.is_some() {
+            ticket_box.seller_fee_basis_points = seller_fee_basis_points.unwrap();
+        }
+        if creators.is_some() {
+            ticket_box.creators = creators.clone().unwrap();
+        }
+        validate_royalties(ticket_box.seller_fee_basis_points, &ticket_box.creators)?;
     }
 
-    if price.is_some() {
-        ctx.accounts.ticket_box.price = price.unwrap();
+    if uri_mode.is_some() {
+        ctx.accounts.ticket_box.uri_mode = uri_mode.unwrap();
     }
 
-    if transferable.is_some() {
-        ctx.accounts.ticket_box.transferable = transferable.unwrap();
+    if symbol.is_some() {
+        ctx.accounts.ticket_box.symbol = symbol.clone().unwrap();
+    }
+
+    if name_format.is_some() {
+        ctx.accounts.ticket_box.name_format = name_format.clone().unwrap();
+    }
+
+    // the longest ticket name depends on all of these
+    if name.is_some()
+        || symbol.is_some()
+        || name_format.is_some()
+        || num_of_tickets != OptionUpdate::Keep
+    {
+        let ticket_box = &ctx.accounts.ticket_box;
+        validate_naming(
+            &ticket_box.name,
+            &ticket_box.symbol,
+            &ticket_box.name_format,
+            ticket_box.num_of_tickets,
+        )?;
+    }
+
+    // so does the longest templated uri, tier uris are checked again on mint
+    if uri.is_some() || uri_mode.is_some() || num_of_tickets != OptionUpdate::Keep {
+        let ticket_box = &ctx.accounts.ticket_box;
+        validate_uri(&ticket_box.uri, ticket_box.uri_mode, ticket_box.num_of_tickets)?;
+    }
+
+    let clock = Clock::get()?;
+    validate_sale(
+        &ctx.accounts.ticket_box.sale_config(),
+        Some(&previous_sale),
+        clock.unix_timestamp,
+    )?;
+
+    // marketplaces show the collection NFT, keep it in sync with the box
+    if name.is_some()
+        || uri.is_some()
+        || symbol.is_some()
+        || seller_fee_basis_points.is_some()
+        || creators.is_some()
+    {
+        let ticket_box = &ctx.accounts.ticket_box;
+        update_metadata(UpdateMetadataParams {
+            ticket_box,
+            metadata: ctx.accounts.collection_metadata.to_account_info(),
+            name: ticket_box.name.clone(),
+            uri: ticket_box.uri.clone(),
+        })?;
     }
 
-    // TODO update collection nft
+    emit!(TicketBoxUpdated {
+        ticket_box: ctx.accounts.ticket_box.key(),
+        authority: ctx.accounts.authority.key(),
+        name,
+        uri,
+        start_at,
+        end_at,
+        num_of_tickets,
+        ticket_per_wallet,
+        price,
+        transferable,
+        currency,
+        seller_fee_basis_points,
+        creators,
+        uri_mode,
+        symbol,
+        name_format,
+    });
 
     Ok(())
 }
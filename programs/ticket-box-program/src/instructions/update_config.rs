@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::{Config, ConfigUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ ErrorCode::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateConfig>,
+    admin: Option<Pubkey>,
+    treasury: Option<Pubkey>,
+    fee_bps: Option<u16>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    if let Some(admin) = admin {
+        config.admin = admin;
+    }
+
+    if let Some(treasury) = treasury {
+        config.treasury = treasury;
+    }
+
+    if let Some(fee_bps) = fee_bps {
+        require!(fee_bps <= Config::MAX_FEE_BPS, ErrorCode::InvalidFeeBps);
+        config.fee_bps = fee_bps;
+    }
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        treasury: config.treasury,
+        fee_bps: config.fee_bps,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use {
+    anchor_lang::prelude::*,
+    mpl_token_metadata::{
+        state::{DataV2, Metadata, TokenMetadataAccount},
+        ID as MPL_TOKEN_METADATA_ID,
+    },
+};
+
+use crate::{assert_keys_equal, cmp_pubkeys, errors::ErrorCode, format_ticket_name};
+use crate::{update_metadata_data, Ticket, TicketBox, TicketMetadataUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateTicketMetadata<'info> {
+    pub creator: Signer<'info>,
+    #[account(
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+
+    /// CHECK: checked against the Metaplex program id
+    #[account(address = MPL_TOKEN_METADATA_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    // remaining accounts, in pairs
+    // ticket_metadata_info
+    // ticket_info
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateTicketMetadata<'info>>,
+    name_format: Option<String>,
+    uri: Option<String>,
+) -> Result<()> {
+    let ticket_box = &ctx.accounts.ticket_box;
+
+    let pairs = ctx.remaining_accounts.chunks_exact(2);
+    require!(
+        !ctx.remaining_accounts.is_empty() && pairs.remainder().is_empty(),
+        ErrorCode::InvalidRelatedAccount
+    );
+
+    if let Some(name_format) = &name_format {
+        require!(
+            name_format.len() <= TicketBox::MAX_NAME_FORMAT_LEN,
+            ErrorCode::NameFormatTooLong
+        );
+    }
+
+    let mut ticket_mints = vec![];
+    for accounts in pairs {
+        let ticket_metadata_info = &accounts[0];
+        let ticket = Account::<Ticket>::try_from(&accounts[1])?;
+        require!(
+            cmp_pubkeys(&ticket.ticket_box, &ticket_box.key())
+                && cmp_pubkeys(&ticket.collection_mint, &ticket_box.collection_mint),
+            ErrorCode::InvalidRelatedAccount
+        );
+        let (ticket_metadata_key, _) = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                MPL_TOKEN_METADATA_ID.as_ref(),
+                ticket.mint.as_ref(),
+            ],
+            &MPL_TOKEN_METADATA_ID,
+        );
+        assert_keys_equal(ticket_metadata_info.key, &ticket_metadata_key)?;
+
+        // sold tickets keep their symbol and royalties, only the name and uri can change
+        let metadata = Metadata::from_account_info(ticket_metadata_info)?;
+
+        // a new format is filled in with the box name, otherwise the current name is kept
+        let name = match &name_format {
+            Some(name_format) => format_ticket_name(name_format, &ticket_box.name, ticket.serial),
+            None => trim_padding(&metadata.data.name),
+        };
+        require!(
+            name.len() <= TicketBox::MAX_NAME_LEN,
+            ErrorCode::NameFormatTooLong
+        );
+
+        // a new uri is templated per ticket, otherwise the current one is kept
+        let uri = match &uri {
+            Some(uri) => uri.replace(TicketBox::SERIAL_PLACEHOLDER, &ticket.serial.to_string()),
+            None => trim_padding(&metadata.data.uri),
+        };
+        require!(uri.len() <= TicketBox::MAX_URI_LEN, ErrorCode::UriTooLong);
+
+        update_metadata_data(
+            ticket_box,
+            ticket_metadata_info.clone(),
+            DataV2 {
+                name,
+                symbol: trim_padding(&metadata.data.symbol),
+                uri,
+                seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+                creators: metadata.data.creators,
+                collection: metadata.collection,
+                uses: metadata.uses,
+            },
+        )?;
+        ticket_mints.push(ticket.mint);
+    }
+
+    emit!(TicketMetadataUpdated {
+        ticket_box: ticket_box.key(),
+        ticket_mints,
+        name_format,
+        uri,
+    });
+
+    Ok(())
+}
+
+/// Metaplex pads metadata strings to their max length with `\0`.
+fn trim_padding(value: &str) -> String {
+    value.trim_end_matches(char::from(0)).to_string()
+}
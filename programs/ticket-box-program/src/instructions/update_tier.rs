@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::{validate_sale, validate_uri, OptionUpdate, SaleConfig};
+use crate::{TicketBox, TicketTier, TicketTierUpdated};
+
+#[derive(Accounts)]
+pub struct UpdateTier<'info> {
+    pub creator: Signer<'info>,
+    #[account(
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    #[account(
+        mut,
+        has_one = ticket_box,
+        constraint = ticket_tier.collection_mint == ticket_box.collection_mint
+            @ ErrorCode::InvalidRelatedAccount,
+        seeds = [b"ticket_tier", ticket_box.key().as_ref(), &[ticket_tier.index]],
+        bump,
+    )]
+    pub ticket_tier: Account<'info, TicketTier>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateTier>,
+    name: Option<String>,
+    uri: Option<String>,
+    start_at: Option<i64>,
+    end_at: OptionUpdate,
+    num_of_tickets: OptionUpdate,
+    ticket_per_wallet: OptionUpdate,
+    price: Option<u64>,
+) -> Result<()> {
+    let ticket_tier = &mut ctx.accounts.ticket_tier;
+    // tiers share the box proceeds
+    let withdrawn = ctx.accounts.ticket_box.withdrawn;
+    let previous_sale = SaleConfig {
+        withdrawn,
+        ..ticket_tier.sale_config()
+    };
+
+    if let Some(name) = &name {
+        require!(
+            name.len() <= TicketBox::MAX_NAME_LEN,
+            ErrorCode::NameTooLong
+        );
+        ticket_tier.name = name.clone();
+    }
+
+    if let Some(uri) = &uri {
+        let ticket_box = &ctx.accounts.ticket_box;
+        validate_uri(uri, ticket_box.uri_mode, ticket_box.num_of_tickets)?;
+        ticket_tier.uri = uri.clone();
+    }
+
+    if let Some(start_at) = start_at {
+        ticket_tier.start_at = start_at;
+    }
+
+    ticket_tier.end_at = end_at.apply(ticket_tier.end_at);
+
+    ticket_tier.num_of_tickets = num_of_tickets.apply(ticket_tier.num_of_tickets);
+
+    ticket_tier.ticket_per_wallet = ticket_per_wallet.apply(ticket_tier.ticket_per_wallet);
+
+    if let Some(price) = price {
+        ticket_tier.price = price;
+    }
+
+    let clock = Clock::get()?;
+    validate_sale(
+        &SaleConfig {
+            withdrawn,
+            ..ticket_tier.sale_config()
+        },
+        Some(&previous_sale),
+        clock.unix_timestamp,
+    )?;
+
+    emit!(TicketTierUpdated {
+        ticket_box: ticket_tier.ticket_box,
+        ticket_tier: ticket_tier.key(),
+        name,
+        uri,
+        start_at,
+        end_at,
+        num_of_tickets,
+        ticket_per_wallet,
+        price,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use {
+    anchor_lang::{
+        prelude::*,
+        solana_program::{
+            account_info::next_account_info, program::invoke_signed, system_instruction,
+        },
+    },
+    anchor_spl::token,
+    mpl_token_metadata::utils::assert_owned_by,
+};
+
+use crate::{assert_initialized, assert_keys_equal, cmp_pubkeys, errors::ErrorCode};
+use crate::{spl_token_transfer, ProceedsWithdrawn, TicketBox, TokenTransferParams};
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        has_one = creator,
+        seeds = [b"ticket_box", ticket_box.uuid.as_bytes(), creator.key().as_ref()],
+        bump = ticket_box.bump,
+    )]
+    pub ticket_box: Account<'info, TicketBox>,
+    /// CHECK: system-owned PDA holding the box's SOL proceeds
+    #[account(
+        mut,
+        seeds = [b"vault", ticket_box.key().as_ref()],
+        bump = ticket_box.vault_bump
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    // native
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, token::Token>,
+    // remaining accounts
+    // vault_token_account_info
+    // creator_token_account_info
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>) -> Result<()> {
+    let ticket_box = &mut ctx.accounts.ticket_box;
+    let vault = &ctx.accounts.vault;
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    // proceeds of a cancelled event are kept for refunds
+    require!(!ticket_box.cancelled, ErrorCode::EventCancelled);
+
+    require!(
+        ticket_box.end_at.is_some() && ticket_box.end_at.unwrap() <= current_timestamp,
+        ErrorCode::EventNotEnded
+    );
+
+    let ticket_box_key = ticket_box.key();
+    let signer_seeds = [
+        b"vault".as_ref(),
+        ticket_box_key.as_ref(),
+        &[ticket_box.vault_bump],
+    ];
+
+    let amount = if let Some(mint) = ticket_box.currency {
+        let vault_token_account_info = next_account_info(remaining_accounts)?;
+        let creator_token_account_info = next_account_info(remaining_accounts)?;
+
+        assert_keys_equal(vault_token_account_info.key, &ticket_box.escrow)?;
+        let vault_token_account: token::spl_token::state::Account =
+            assert_initialized(vault_token_account_info)?;
+
+        let creator_token_account: token::spl_token::state::Account =
+            assert_initialized(creator_token_account_info)?;
+        assert_owned_by(creator_token_account_info, &token::spl_token::id())?;
+        if !cmp_pubkeys(&creator_token_account.mint, &mint) {
+            return err!(ErrorCode::MintMismatch);
+        }
+
+        if vault_token_account.amount > 0 {
+            spl_token_transfer(TokenTransferParams {
+                source: vault_token_account_info.clone(),
+                destination: creator_token_account_info.clone(),
+                amount: vault_token_account.amount,
+                authority: vault.to_account_info(),
+                authority_signer_seeds: &signer_seeds,
+                token_program: ctx.accounts.token_program.to_account_info(),
+            })?;
+        }
+        vault_token_account.amount
+    } else {
+        // the vault keeps its rent exempt reserve
+        let rent_exempt_lamports = Rent::get()?.minimum_balance(0);
+        let amount = vault.lamports().saturating_sub(rent_exempt_lamports);
+        if amount > 0 {
+            invoke_signed(
+                &system_instruction::transfer(&vault.key(), &ctx.accounts.creator.key(), amount),
+                &[
+                    vault.to_account_info(),
+                    ctx.accounts.creator.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+                &[&signer_seeds],
+            )?;
+        }
+        amount
+    };
+
+    // the sale is settled, see `validate_sale`
+    ticket_box.withdrawn = true;
+
+    emit!(ProceedsWithdrawn {
+        ticket_box: ticket_box.key(),
+        creator: ctx.accounts.creator.key(),
+        amount,
+        currency: ticket_box.currency,
+    });
+
+    Ok(())
+}
@@ -6,9 +6,15 @@ pub use state::*;
 pub mod utils;
 pub use utils::*;
 
+pub mod validation;
+pub use validation::*;
+
 pub mod errors;
 pub use errors::*;
 
+pub mod events;
+pub use events::*;
+
 pub mod instructions;
 pub use instructions::*;
 
@@ -19,8 +25,8 @@ pub mod ticket_box_program {
 
     use super::*;
 
-    pub fn initialize(
-        ctx: Context<Initialize>,
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
         uuid: String,
         name: String,
         uri: String,
@@ -30,6 +36,11 @@ pub mod ticket_box_program {
         ticket_per_wallet: Option<i64>,
         price: u64,
         transferable: bool,
+        seller_fee_basis_points: u16,
+        creators: Vec<TicketBoxCreator>,
+        uri_mode: UriMode,
+        symbol: String,
+        name_format: String,
     ) -> Result<()> {
         initialize::handler(
             ctx,
@@ -42,19 +53,30 @@ pub mod ticket_box_program {
             ticket_per_wallet,
             price,
             transferable,
+            seller_fee_basis_points,
+            creators,
+            uri_mode,
+            symbol,
+            name_format,
         )
     }
 
-    pub fn update(
-        ctx: Context<UpdateTicketBox>,
+    pub fn update<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateTicketBox<'info>>,
         name: Option<String>,
         uri: Option<String>,
         start_at: Option<i64>,
-        end_at: Option<i64>,
-        num_of_tickets: Option<i64>,
-        ticket_per_wallet: Option<i64>,
+        end_at: OptionUpdate,
+        num_of_tickets: OptionUpdate,
+        ticket_per_wallet: OptionUpdate,
         price: Option<u64>,
         transferable: Option<bool>,
+        currency: CurrencyUpdate,
+        seller_fee_basis_points: Option<u16>,
+        creators: Option<Vec<TicketBoxCreator>>,
+        uri_mode: Option<UriMode>,
+        symbol: Option<String>,
+        name_format: Option<String>,
     ) -> Result<()> {
         update::handler(
             ctx,
@@ -66,13 +88,137 @@ pub mod ticket_box_program {
             ticket_per_wallet,
             price,
             transferable,
+            currency,
+            seller_fee_basis_points,
+            creators,
+            uri_mode,
+            symbol,
+            name_format,
         )
     }
 
     pub fn mint<'info>(
         ctx: Context<'_, '_, '_, 'info, MintTicket<'info>>,
         uri: String,
+        max_price: u64,
+        currency: Option<Pubkey>,
+    ) -> Result<()> {
+        mint::handler(ctx, uri, max_price, currency)
+    }
+
+    pub fn withdraw<'info>(ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>) -> Result<()> {
+        withdraw::handler(ctx)
+    }
+
+    pub fn cancel_event<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelEvent<'info>>,
+    ) -> Result<()> {
+        cancel_event::handler(ctx)
+    }
+
+    pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+        refund::handler(ctx)
+    }
+
+    pub fn check_in(ctx: Context<CheckIn>) -> Result<()> {
+        check_in::handler(ctx)
+    }
+
+    pub fn add_operator(
+        ctx: Context<AddOperator>,
+        authority: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        add_operator::handler(ctx, authority, permissions)
+    }
+
+    pub fn remove_operator(ctx: Context<RemoveOperator>) -> Result<()> {
+        remove_operator::handler(ctx)
+    }
+
+    pub fn thaw_ticket(ctx: Context<ThawTicket>) -> Result<()> {
+        thaw_ticket::handler(ctx)
+    }
+
+    pub fn add_tier(
+        ctx: Context<AddTier>,
+        name: String,
+        uri: String,
+        start_at: i64,
+        end_at: Option<i64>,
+        num_of_tickets: Option<i64>,
+        ticket_per_wallet: Option<i64>,
+        price: u64,
+    ) -> Result<()> {
+        add_tier::handler(
+            ctx,
+            name,
+            uri,
+            start_at,
+            end_at,
+            num_of_tickets,
+            ticket_per_wallet,
+            price,
+        )
+    }
+
+    pub fn update_tier(
+        ctx: Context<UpdateTier>,
+        name: Option<String>,
+        uri: Option<String>,
+        start_at: Option<i64>,
+        end_at: OptionUpdate,
+        num_of_tickets: OptionUpdate,
+        ticket_per_wallet: OptionUpdate,
+        price: Option<u64>,
+    ) -> Result<()> {
+        update_tier::handler(
+            ctx,
+            name,
+            uri,
+            start_at,
+            end_at,
+            num_of_tickets,
+            ticket_per_wallet,
+            price,
+        )
+    }
+
+    pub fn close_ticket_box<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseTicketBox<'info>>,
+    ) -> Result<()> {
+        close_ticket_box::handler(ctx)
+    }
+
+    pub fn migrate_ticket_box<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateTicketBox<'info>>,
+    ) -> Result<()> {
+        migrate_ticket_box::handler(ctx)
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        admin: Pubkey,
+        treasury: Pubkey,
+        fee_bps: u16,
+    ) -> Result<()> {
+        initialize_config::handler(ctx, admin, treasury, fee_bps)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        admin: Option<Pubkey>,
+        treasury: Option<Pubkey>,
+        fee_bps: Option<u16>,
+    ) -> Result<()> {
+        update_config::handler(ctx, admin, treasury, fee_bps)
+    }
+
+    pub fn update_ticket_metadata<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateTicketMetadata<'info>>,
+        name_format: Option<String>,
+        uri: Option<String>,
     ) -> Result<()> {
-        mint::handler(ctx, uri)
+        update_ticket_metadata::handler(ctx, name_format, uri)
     }
 }
@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 
 #[account]
 pub struct TicketBox {
+    pub version: u8, // layout version, see `migrate_ticket_box`
+    pub bump: u8,
     pub uuid: String,
     pub creator: Pubkey,
     pub name: String,
@@ -14,15 +16,119 @@ pub struct TicketBox {
     pub currency: Option<Pubkey>, // None for sol
     pub price: u64, // 0 for free
     pub transferable: bool,
+    pub cancelled: bool,
+    pub withdrawn: bool, // proceeds were withdrawn, the sale is settled
+    pub protocol_fees: u64, // paid to the treasury on sales, put back by the creator on cancel
     pub escrow: Pubkey,
+    pub vault_bump: u8,
+    pub collection_mint: Pubkey,
+    pub num_of_tiers: u8, // 0 for a single-priced box
+    pub seller_fee_basis_points: u16, // royalty on secondary sales
+    pub creators: Vec<TicketBoxCreator>, // royalty recipients, shares sum to 100
+    pub uri_mode: UriMode, // how each ticket gets its metadata uri
+    pub symbol: String, // NFT symbol of the tickets and the collection
+    pub name_format: String, // ticket NFT name, with `{name}` and `{serial}` placeholders
 }
 
 impl TicketBox {
+    pub const VERSION: u8 = 1;
+
+    pub const MAX_UUID_LEN: usize = 32; // max seed length
+    pub const MAX_NAME_LEN: usize = 32; // Metaplex limit
+    pub const MAX_URI_LEN: usize = 200; // Metaplex limit
+    pub const MAX_SYMBOL_LEN: usize = 10; // Metaplex limit
+    pub const MAX_NAME_FORMAT_LEN: usize = 32;
+
     pub const SIZE: usize = 8 // discriminator
-    + (4 + 256) // uuid, max = 256
+    + 1 // version
+    + 1 // bump
+    + (4 + Self::MAX_UUID_LEN) // uuid
     + 32 // creator
-    + (4 + 256) // name, max = 256
-    + (4 + 1000) // uri, max = 1000
+    + (4 + Self::MAX_NAME_LEN) // name
+    + (4 + Self::MAX_URI_LEN) // uri
+    + 8 // start_at
+    + (1 + 8) // end_at
+    + (1 + 8) // num_of_tickets
+    + 8 // num_of_sold
+    + (1 + 8) // ticket_per_wallet
+    + (1 + 32) // currency
+    + 8 // price
+    + 1 // transferable
+    + 1 // cancelled
+    + 1 // withdrawn
+    + 8 // protocol_fees
+    + 32 // escrow
+    + 1 // vault_bump
+    + 32 // collection_mint
+    + 1 // num_of_tiers
+    + 2 // seller_fee_basis_points
+    + (4 + Self::MAX_CREATORS * TicketBoxCreator::SIZE) // creators
+    + 1 // uri_mode
+    + (4 + Self::MAX_SYMBOL_LEN) // symbol
+    + (4 + Self::MAX_NAME_FORMAT_LEN); // name_format
+
+    pub const MAX_TIERS: u8 = 8;
+    // Metaplex allows 5 creators, the box PDA takes one slot
+    pub const MAX_CREATORS: usize = 4;
+    pub const MAX_SELLER_FEE_BASIS_POINTS: u16 = 10_000;
+    // royalty hard-coded on the NFTs of boxes created before it was configurable
+    pub const DEFAULT_SELLER_FEE_BASIS_POINTS: u16 = 200;
+
+    // replaced by the ticket serial in `UriMode::Template` uris
+    pub const SERIAL_PLACEHOLDER: &str = "{serial}";
+    // replaced by the box name in `name_format`
+    pub const NAME_PLACEHOLDER: &str = "{name}";
+
+    // ticket naming of boxes created before it was configurable
+    pub const DEFAULT_SYMBOL: &str = "TICKET";
+    pub const DEFAULT_NAME_FORMAT: &str = "{name}{serial}";
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UriMode {
+    Shared,        // every ticket uses the box or tier uri
+    Template,      // the box or tier uri with `{serial}` replaced
+    CreatorSigned, // the uri passed to `mint`, co-signed by the creator
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct TicketBoxCreator {
+    pub address: Pubkey,
+    pub share: u8,
+}
+
+impl TicketBoxCreator {
+    pub const SIZE: usize = 32 // address
+    + 1; // share
+}
+
+/// `TicketBox` layout before versioning. These accounts were allocated with
+/// exactly `TicketBoxV0::SIZE` bytes, which is how `migrate_ticket_box` tells
+/// them apart.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TicketBoxV0 {
+    pub uuid: String,
+    pub creator: Pubkey,
+    pub name: String,
+    pub uri: String,
+    pub start_at: i64,
+    pub end_at: Option<i64>,
+    pub num_of_tickets: Option<i64>,
+    pub num_of_sold: i64,
+    pub ticket_per_wallet: Option<i64>,
+    pub currency: Option<Pubkey>,
+    pub price: u64,
+    pub transferable: bool,
+    pub escrow: Pubkey,
+}
+
+impl TicketBoxV0 {
+    // as allocated by the unversioned program, tags not included
+    pub const SIZE: usize = 8 // discriminator
+    + (4 + 256) // uuid
+    + 32 // creator
+    + (4 + 256) // name
+    + (4 + 1000) // uri
     + 8 // start_at
     + 8 // end_at
     + 8 // num_of_tickets
@@ -34,6 +140,153 @@ impl TicketBox {
     + 32; // escrow
 }
 
+#[account]
+pub struct TicketTier {
+    pub ticket_box: Pubkey,
+    pub index: u8,
+    pub name: String,
+    pub uri: String,
+    pub start_at: i64,
+    pub end_at: Option<i64>,         // None for unlimit
+    pub num_of_tickets: Option<i64>, // None for unlimit
+    pub num_of_sold: i64,
+    pub ticket_per_wallet: Option<i64>,
+    pub price: u64, // in the box currency, 0 for free
+    pub collection_mint: Pubkey, // of the box at creation, a box re-created with the same uuid has another
+}
+
+impl TicketTier {
+    pub const SIZE: usize = 8 // discriminator
+    + 32 // ticket_box
+    + 1 // index
+    + (4 + TicketBox::MAX_NAME_LEN) // name
+    + (4 + TicketBox::MAX_URI_LEN) // uri
+    + 8 // start_at
+    + (1 + 8) // end_at
+    + (1 + 8) // num_of_tickets
+    + 8 // num_of_sold
+    + (1 + 8) // ticket_per_wallet
+    + 8 // price
+    + 32; // collection_mint
+}
+
+/// Update to an optional field, where `None` would mean unlimited.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OptionUpdate {
+    Keep,
+    Set { value: i64 },
+    Clear,
+}
+
+impl OptionUpdate {
+    pub fn apply(self, current: Option<i64>) -> Option<i64> {
+        match self {
+            OptionUpdate::Keep => current,
+            OptionUpdate::Set { value } => Some(value),
+            OptionUpdate::Clear => None,
+        }
+    }
+}
+
+/// Update to the payment currency of a box.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurrencyUpdate {
+    Keep,
+    Sol,
+    Token { mint: Pubkey },
+}
+
+#[account]
+pub struct PurchaseRecord {
+    pub ticket_box: Pubkey,
+    pub buyer: Pubkey,
+    pub num_of_minted: i64,
+    pub tier_num_of_minted: [i64; 8], // indexed by `TicketTier.index`
+    pub collection_mint: Pubkey, // of the box at creation, a box re-created with the same uuid has another
+}
+
+impl PurchaseRecord {
+    pub const SIZE: usize = 8 // discriminator
+    + 32 // ticket_box
+    + 32 // buyer
+    + 8 // num_of_minted
+    + 8 * TicketBox::MAX_TIERS as usize // tier_num_of_minted
+    + 32; // collection_mint
+}
+
+#[account]
+pub struct Ticket {
+    pub ticket_box: Pubkey,
+    pub mint: Pubkey,
+    pub serial: i64,
+    pub price: u64, // price paid, refunded on cancellation
+    pub checked_in_at: Option<i64>, // None until redeemed at the door
+    pub tier: Option<u8>, // `TicketTier.index`, None for a single-priced box
+    pub protocol_fee: u64, // part of `price` paid to the treasury, see `TicketBox.protocol_fees`
+    pub collection_mint: Pubkey, // of the box at mint, a box re-created with the same uuid has another
+}
+
+impl Ticket {
+    pub const SIZE: usize = 8 // discriminator
+    + 32 // ticket_box
+    + 32 // mint
+    + 8 // serial
+    + 8 // price
+    + (1 + 8) // checked_in_at
+    + (1 + 1) // tier
+    + 8 // protocol_fee
+    + 32; // collection_mint
+}
+
+#[account]
+pub struct Operator {
+    pub ticket_box: Pubkey,
+    pub authority: Pubkey,
+    pub permissions: u8,
+    pub collection_mint: Pubkey, // of the box at creation, a box re-created with the same uuid has another
+}
+
+impl Operator {
+    pub const SIZE: usize = 8 // discriminator
+    + 32 // ticket_box
+    + 32 // authority
+    + 1 // permissions
+    + 32; // collection_mint
+
+    // permission flags
+    pub const CHECK_IN: u8 = 1 << 0;
+    pub const UPDATE_METADATA: u8 = 1 << 1;
+    pub const ALL_PERMISSIONS: u8 = Self::CHECK_IN | Self::UPDATE_METADATA;
+
+    pub fn has_permission(&self, permission: u8) -> bool {
+        self.permissions & permission == permission
+    }
+}
+
+/// Platform settings, a single PDA for the whole program.
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16, // share of every ticket price paid to the treasury
+    pub bump: u8,
+}
+
+impl Config {
+    pub const SIZE: usize = 8 // discriminator
+    + 32 // admin
+    + 32 // treasury
+    + 2 // fee_bps
+    + 1; // bump
+
+    pub const MAX_FEE_BPS: u16 = 10_000;
+
+    pub fn protocol_fee(&self, price: u64) -> u64 {
+        // can't overflow, `fee_bps` is at most 10_000
+        (price as u128 * self.fee_bps as u128 / Self::MAX_FEE_BPS as u128) as u64
+    }
+}
+
 #[account]
 pub struct CollectionPda {
     pub authority: Pubkey,
@@ -1,12 +1,15 @@
 use anchor_lang::{
     prelude::*,
-    solana_program::{program_pack::{IsInitialized, Pack}, program_memory::sol_memcmp, pubkey::PUBKEY_BYTES, program::invoke_signed},
+    solana_program::{program_pack::{IsInitialized, Pack}, program_memory::sol_memcmp, pubkey::PUBKEY_BYTES, program::{invoke, invoke_signed}, system_instruction},
 };
 
-use anchor_spl::{token, associated_token::get_associated_token_address};
-use mpl_token_metadata::utils::assert_owned_by;
+use anchor_spl::{token, associated_token::{self, get_associated_token_address}};
+use mpl_token_metadata::{instruction as mpl_instruction, utils::assert_owned_by, ID as MPL_TOKEN_METADATA_ID};
+use mpl_token_metadata::state::CollectionDetails;
+use mpl_token_metadata::state::{Creator, DataV2, Metadata, TokenMetadataAccount};
 
 use crate::errors::ErrorCode;
+use crate::{Operator, TicketBox, TicketBoxCreator};
 
 pub fn assert_initialized<T: Pack + IsInitialized>(account_info: &AccountInfo) -> Result<T> {
     let account: T = T::unpack_unchecked(&account_info.data.borrow())?;
@@ -43,6 +46,55 @@ pub fn assert_keys_equal(key1: &Pubkey, key2: &Pubkey) -> Result<()> {
     }
 }
 
+/// Passes for the box creator, otherwise `operator_info` has to be an
+/// `Operator` of the box for `authority` holding `permission`.
+pub fn assert_permission(
+    ticket_box: &Account<TicketBox>,
+    authority: &Pubkey,
+    operator_info: Option<&AccountInfo>,
+    permission: u8,
+) -> Result<()> {
+    if cmp_pubkeys(authority, &ticket_box.creator) {
+        return Ok(());
+    }
+
+    let operator_info = operator_info.ok_or(ErrorCode::Unauthorized)?;
+    let operator: Account<Operator> =
+        Account::try_from(operator_info).map_err(|_| ErrorCode::Unauthorized)?;
+    if !cmp_pubkeys(&operator.ticket_box, &ticket_box.key())
+        || !cmp_pubkeys(&operator.collection_mint, &ticket_box.collection_mint)
+        || !cmp_pubkeys(&operator.authority, authority)
+        || !operator.has_permission(permission)
+    {
+        return err!(ErrorCode::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// Ticket NFT name, `name_format` with the box name and serial filled in.
+pub fn format_ticket_name(name_format: &str, name: &str, serial: i64) -> String {
+    // serial first, so a `{serial}` inside the box name stays as is
+    name_format
+        .replace(TicketBox::SERIAL_PLACEHOLDER, &serial.to_string())
+        .replace(TicketBox::NAME_PLACEHOLDER, name)
+}
+
+/// Creators of the box NFTs, the box PDA signs as a verified creator without a share.
+pub fn metadata_creators(ticket_box: &Pubkey, creators: &[TicketBoxCreator]) -> Vec<Creator> {
+    let mut metadata_creators = vec![Creator {
+        address: *ticket_box,
+        verified: true,
+        share: 0,
+    }];
+    metadata_creators.extend(creators.iter().map(|creator| Creator {
+        address: creator.address,
+        verified: false,
+        share: creator.share,
+    }));
+    metadata_creators
+}
+
 pub struct TokenTransferParams<'a: 'b, 'b> {
     /// CHECK: account checked in CPI
     pub source: AccountInfo<'a>,
@@ -87,4 +139,351 @@ pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> Result<()> {
     );
 
     result.map_err(|_| ErrorCode::TokenTransferFailed.into())
-}
\ No newline at end of file
+}
+
+pub struct ThawTicketParams<'a: 'b, 'b> {
+    pub ticket_box: &'b Account<'a, TicketBox>,
+    /// CHECK: account checked in CPI
+    pub ticket_token_account: AccountInfo<'a>,
+    /// CHECK: account checked in CPI
+    pub ticket_master_edition: AccountInfo<'a>,
+    /// CHECK: account checked in CPI
+    pub ticket_mint: AccountInfo<'a>,
+    /// CHECK: account checked in CPI
+    pub token_program: AccountInfo<'a>,
+}
+
+/// Thaws a non-transferable ticket frozen at mint through the ticket box delegate.
+pub fn thaw_ticket_account(params: ThawTicketParams<'_, '_>) -> Result<()> {
+    let ThawTicketParams {
+        ticket_box,
+        ticket_token_account,
+        ticket_master_edition,
+        ticket_mint,
+        token_program,
+    } = params;
+
+    let signer_seeds = [
+        b"ticket_box".as_ref(),
+        ticket_box.uuid.as_ref(),
+        ticket_box.creator.as_ref(),
+        &[ticket_box.bump],
+    ];
+
+    invoke_signed(
+        &mpl_instruction::thaw_delegated_account(
+            MPL_TOKEN_METADATA_ID,
+            ticket_box.key(),
+            ticket_token_account.key(),
+            ticket_master_edition.key(),
+            ticket_mint.key(),
+        ),
+        &[
+            ticket_box.to_account_info(),
+            ticket_token_account,
+            ticket_master_edition,
+            ticket_mint,
+            token_program,
+        ],
+        &[&signer_seeds],
+    )?;
+
+    Ok(())
+}
+
+pub struct UpdateMetadataParams<'a: 'b, 'b> {
+    pub ticket_box: &'b Account<'a, TicketBox>,
+    /// CHECK: account checked in CPI
+    pub metadata: AccountInfo<'a>,
+    pub name: String,
+    pub uri: String,
+}
+
+/// Rewrites the metadata of a box NFT with the box royalties and symbol, signed by the box PDA.
+pub fn update_metadata(params: UpdateMetadataParams<'_, '_>) -> Result<()> {
+    let UpdateMetadataParams {
+        ticket_box,
+        metadata,
+        name,
+        uri,
+    } = params;
+
+    // keep the collection, and the creators that verified themselves
+    let current = Metadata::from_account_info(&metadata)?;
+    let current_creators = current.data.creators.unwrap_or_default();
+    let creators = metadata_creators(&ticket_box.key(), &ticket_box.creators)
+        .into_iter()
+        .map(|mut creator| {
+            creator.verified |= current_creators.iter().any(|current| {
+                current.verified && cmp_pubkeys(&current.address, &creator.address)
+            });
+            creator
+        })
+        .collect();
+
+    update_metadata_data(
+        ticket_box,
+        metadata,
+        DataV2 {
+            name,
+            symbol: ticket_box.symbol.clone(),
+            uri,
+            seller_fee_basis_points: ticket_box.seller_fee_basis_points,
+            creators: Some(creators),
+            collection: current.collection,
+            uses: current.uses,
+        },
+    )
+}
+
+/// Rewrites the metadata of a box NFT with `data` as is, signed by the box PDA.
+pub fn update_metadata_data<'a>(
+    ticket_box: &Account<'a, TicketBox>,
+    metadata: AccountInfo<'a>,
+    data: DataV2,
+) -> Result<()> {
+    let signer_seeds = [
+        b"ticket_box".as_ref(),
+        ticket_box.uuid.as_ref(),
+        ticket_box.creator.as_ref(),
+        &[ticket_box.bump],
+    ];
+
+    invoke_signed(
+        &mpl_instruction::update_metadata_accounts_v2(
+            MPL_TOKEN_METADATA_ID,
+            metadata.key(),
+            ticket_box.key(),
+            None,
+            Some(data),
+            None,
+            None,
+        ),
+        &[metadata, ticket_box.to_account_info()],
+        &[&signer_seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Creates a program-owned PDA like Anchor's `init`, for accounts whose seeds are checked in the handler.
+pub fn create_pda_account<'a>(
+    payer: AccountInfo<'a>,
+    account: AccountInfo<'a>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+    system_program: AccountInfo<'a>,
+    rent: &Rent,
+) -> Result<()> {
+    let rent_exempt_lamports = rent.minimum_balance(space);
+    if account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                rent_exempt_lamports,
+                space as u64,
+                &crate::ID,
+            ),
+            &[payer, account, system_program],
+            &[signer_seeds],
+        )?;
+        return Ok(());
+    }
+
+    // the address already holds lamports, so it can't be created in one go
+    if account.lamports() < rent_exempt_lamports {
+        invoke(
+            &system_instruction::transfer(
+                payer.key,
+                account.key,
+                rent_exempt_lamports - account.lamports(),
+            ),
+            &[payer, account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, &crate::ID),
+        &[account, system_program],
+        &[signer_seeds],
+    )?;
+
+    Ok(())
+}
+
+pub struct CreateCollectionParams<'a, 'b> {
+    pub ticket_box: &'b TicketBox,
+    /// CHECK: account checked in CPI
+    pub ticket_box_info: AccountInfo<'a>,
+    /// CHECK: mint authority of the collection, pays for its accounts
+    pub creator: AccountInfo<'a>,
+    /// CHECK: account checked in CPI
+    pub collection_mint: AccountInfo<'a>,
+    /// CHECK: account checked in CPI
+    pub collection_metadata: AccountInfo<'a>,
+    /// CHECK: account checked in CPI
+    pub collection_master_edition: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub rent: AccountInfo<'a>,
+}
+
+/// Creates the sized collection NFT of a box from its name, uri and royalties, managed by the box PDA.
+pub fn create_collection(params: CreateCollectionParams<'_, '_>) -> Result<()> {
+    let CreateCollectionParams {
+        ticket_box,
+        ticket_box_info,
+        creator,
+        collection_mint,
+        collection_metadata,
+        collection_master_edition,
+        token_program,
+        system_program,
+        rent,
+    } = params;
+
+    let signer_seeds = [
+        b"ticket_box".as_ref(),
+        ticket_box.uuid.as_ref(),
+        ticket_box.creator.as_ref(),
+        &[ticket_box.bump],
+    ];
+
+    msg!("Creating metadata account...");
+    invoke_signed(
+        &mpl_instruction::create_metadata_accounts_v3(
+            MPL_TOKEN_METADATA_ID,
+            collection_metadata.key(),
+            collection_mint.key(),
+            creator.key(),
+            creator.key(),
+            ticket_box_info.key(),
+            ticket_box.name.clone(),
+            ticket_box.symbol.clone(),
+            ticket_box.uri.clone(),
+            Some(metadata_creators(ticket_box_info.key, &ticket_box.creators)),
+            ticket_box.seller_fee_basis_points,
+            true,
+            true,
+            None,
+            None,
+            Some(CollectionDetails::V1 { size: 0 }),
+        ),
+        &[
+            collection_metadata.clone(),
+            collection_mint.clone(),
+            creator.clone(),
+            creator.clone(),
+            ticket_box_info.clone(),
+            system_program.clone(),
+            rent.clone(),
+        ],
+        &[&signer_seeds],
+    )?;
+
+    msg!("Creating master edition metadata account...");
+    invoke_signed(
+        &mpl_instruction::create_master_edition_v3(
+            MPL_TOKEN_METADATA_ID,
+            collection_master_edition.key(),
+            collection_mint.key(),
+            ticket_box_info.key(),
+            creator.key(),
+            collection_metadata.key(),
+            creator.key(),
+            Some(0), // max_supply: Option<u64>
+        ),
+        &[
+            collection_master_edition,
+            collection_mint,
+            ticket_box_info,
+            creator.clone(),
+            creator,
+            collection_metadata,
+            token_program,
+            system_program,
+            rent,
+        ],
+        &[&signer_seeds],
+    )?;
+
+    msg!("Collection mint process completed successfully.");
+
+    Ok(())
+}
+
+/// Tops the vault up to its rent exempt minimum so it can receive any amount.
+pub fn fund_vault<'a>(
+    payer: AccountInfo<'a>,
+    vault: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    rent: &Rent,
+) -> Result<()> {
+    let rent_exempt_lamports = rent.minimum_balance(0);
+    if vault.lamports() < rent_exempt_lamports {
+        invoke(
+            &system_instruction::transfer(
+                payer.key,
+                vault.key,
+                rent_exempt_lamports - vault.lamports(),
+            ),
+            &[payer, vault, system_program],
+        )?;
+    }
+
+    Ok(())
+}
+
+pub struct InitVaultTokenAccountParams<'a> {
+    pub payer: AccountInfo<'a>,
+    /// CHECK: vault PDA of the box
+    pub vault: AccountInfo<'a>,
+    /// CHECK: created here when empty, checked to be the vault's ATA
+    pub vault_token_account: AccountInfo<'a>,
+    /// CHECK: checked to be an initialized SPL mint
+    pub currency_mint: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    pub rent: AccountInfo<'a>,
+}
+
+/// Validates the currency mint and creates the vault's escrow ATA for it if missing.
+pub fn init_vault_token_account(params: InitVaultTokenAccountParams<'_>) -> Result<()> {
+    let InitVaultTokenAccountParams {
+        payer,
+        vault,
+        vault_token_account,
+        currency_mint,
+        system_program,
+        token_program,
+        associated_token_program,
+        rent,
+    } = params;
+
+    let _currency_mint: token::spl_token::state::Mint = assert_initialized(&currency_mint)?;
+    assert_owned_by(&currency_mint, &token::spl_token::id())?;
+
+    if vault_token_account.data_is_empty() {
+        associated_token::create(CpiContext::new(
+            associated_token_program,
+            associated_token::Create {
+                payer,
+                associated_token: vault_token_account.clone(),
+                authority: vault.clone(),
+                mint: currency_mint.clone(),
+                system_program,
+                token_program,
+                rent,
+            },
+        ))?;
+    }
+    assert_is_ata(&vault_token_account, vault.key, currency_mint.key)?;
+
+    Ok(())
+}
@@ -0,0 +1,595 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::ErrorCode;
+use crate::{format_ticket_name, TicketBox, TicketBoxCreator, TicketTier, UriMode};
+
+/// Sale settings shared by ticket boxes and tiers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaleConfig {
+    pub start_at: i64,
+    pub end_at: Option<i64>,
+    pub num_of_tickets: Option<i64>,
+    pub num_of_sold: i64,
+    pub ticket_per_wallet: Option<i64>,
+    pub price: u64,
+    pub withdrawn: bool, // proceeds were withdrawn, tiers take it from their box
+}
+
+impl TicketBox {
+    pub fn sale_config(&self) -> SaleConfig {
+        SaleConfig {
+            start_at: self.start_at,
+            end_at: self.end_at,
+            num_of_tickets: self.num_of_tickets,
+            num_of_sold: self.num_of_sold,
+            ticket_per_wallet: self.ticket_per_wallet,
+            price: self.price,
+            withdrawn: self.withdrawn,
+        }
+    }
+}
+
+impl TicketTier {
+    pub fn sale_config(&self) -> SaleConfig {
+        SaleConfig {
+            start_at: self.start_at,
+            end_at: self.end_at,
+            num_of_tickets: self.num_of_tickets,
+            num_of_sold: self.num_of_sold,
+            ticket_per_wallet: self.ticket_per_wallet,
+            price: self.price,
+            withdrawn: false,
+        }
+    }
+}
+
+/// Checks `sale` as a whole, `previous` is the config it replaces (None on creation).
+pub fn validate_sale(sale: &SaleConfig, previous: Option<&SaleConfig>, now: i64) -> Result<()> {
+    match previous {
+        None => {
+            require!(sale.start_at >= now, ErrorCode::InvalidTicketBoxStartAt);
+        }
+        Some(previous) => {
+            // withdrawn proceeds settle the sale, nothing about it can change after
+            require!(
+                !previous.withdrawn || sale == previous,
+                ErrorCode::ProceedsAlreadyWithdrawn
+            );
+
+            // the opening can only move while nothing is on sale yet
+            if sale.start_at != previous.start_at {
+                require!(
+                    previous.start_at > now && previous.num_of_sold == 0,
+                    ErrorCode::InvalidTicketBoxStartAt
+                );
+                require!(sale.start_at >= now, ErrorCode::InvalidTicketBoxStartAt);
+            }
+
+            // a new end can't be in the past, which would open withdrawals right away,
+            // and a passed end stays put, withdrawals may already have started
+            if sale.end_at != previous.end_at {
+                if let Some(previous_end_at) = previous.end_at {
+                    require!(previous_end_at > now, ErrorCode::InvalidTicketBoxEndAt);
+                }
+                if let Some(end_at) = sale.end_at {
+                    require!(end_at > now, ErrorCode::InvalidTicketBoxEndAt);
+                }
+            }
+
+            // the price is settled once the sale is over
+            if let Some(previous_end_at) = previous.end_at {
+                require!(
+                    sale.price == previous.price || previous_end_at > now,
+                    ErrorCode::InvalidTicketPrice
+                );
+            }
+        }
+    }
+
+    if let Some(end_at) = sale.end_at {
+        require!(end_at > sale.start_at, ErrorCode::InvalidTicketBoxEndAt);
+    }
+
+    if let Some(num_of_tickets) = sale.num_of_tickets {
+        require!(
+            num_of_tickets > 0 && num_of_tickets >= sale.num_of_sold,
+            ErrorCode::InvalidNumOfTickets
+        );
+    }
+
+    if let Some(ticket_per_wallet) = sale.ticket_per_wallet {
+        require!(ticket_per_wallet > 0, ErrorCode::InvalidTicketPerWallet);
+
+        if let Some(num_of_tickets) = sale.num_of_tickets {
+            require!(
+                ticket_per_wallet <= num_of_tickets,
+                ErrorCode::InvalidTicketPerWallet
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the royalty settings of a box.
+pub fn validate_royalties(
+    seller_fee_basis_points: u16,
+    creators: &[TicketBoxCreator],
+) -> Result<()> {
+    require!(
+        seller_fee_basis_points <= TicketBox::MAX_SELLER_FEE_BASIS_POINTS,
+        ErrorCode::InvalidSellerFeeBasisPoints
+    );
+
+    require!(
+        !creators.is_empty() && creators.len() <= TicketBox::MAX_CREATORS,
+        ErrorCode::InvalidCreators
+    );
+
+    let total_share: u16 = creators.iter().map(|creator| creator.share as u16).sum();
+    require!(total_share == 100, ErrorCode::InvalidCreators);
+
+    Ok(())
+}
+
+/// Checks the NFT naming of a box against the Metaplex limits, for the longest serial it can sell.
+pub fn validate_naming(
+    name: &str,
+    symbol: &str,
+    name_format: &str,
+    num_of_tickets: Option<i64>,
+) -> Result<()> {
+    require!(name.len() <= TicketBox::MAX_NAME_LEN, ErrorCode::NameTooLong);
+    require!(
+        symbol.len() <= TicketBox::MAX_SYMBOL_LEN,
+        ErrorCode::SymbolTooLong
+    );
+    require!(
+        name_format.len() <= TicketBox::MAX_NAME_FORMAT_LEN,
+        ErrorCode::NameFormatTooLong
+    );
+
+    // unlimited boxes are checked against a serial no sale will realistically reach
+    let max_serial = num_of_tickets.unwrap_or(u32::MAX as i64);
+    require!(
+        format_ticket_name(name_format, name, max_serial).len() <= TicketBox::MAX_NAME_LEN,
+        ErrorCode::NameFormatTooLong
+    );
+
+    Ok(())
+}
+
+/// Checks a box or tier uri against the Metaplex limit, templated uris for the longest serial
+/// the box can sell.
+pub fn validate_uri(uri: &str, uri_mode: UriMode, num_of_tickets: Option<i64>) -> Result<()> {
+    require!(uri.len() <= TicketBox::MAX_URI_LEN, ErrorCode::UriTooLong);
+
+    if uri_mode == UriMode::Template {
+        // serials run across tiers, the box supply bounds them all
+        let max_serial = num_of_tickets.unwrap_or(u32::MAX as i64);
+        require!(
+            uri.replace(TicketBox::SERIAL_PLACEHOLDER, &max_serial.to_string())
+                .len()
+                <= TicketBox::MAX_URI_LEN,
+            ErrorCode::UriTooLong
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_000_000;
+
+    fn sale() -> SaleConfig {
+        SaleConfig {
+            start_at: NOW + 10,
+            end_at: Some(NOW + 100),
+            num_of_tickets: Some(100),
+            num_of_sold: 0,
+            ticket_per_wallet: Some(10),
+            price: 1_000,
+            withdrawn: false,
+        }
+    }
+
+    fn live_sale() -> SaleConfig {
+        SaleConfig {
+            start_at: NOW - 10,
+            num_of_sold: 5,
+            ..sale()
+        }
+    }
+
+    fn assert_error(result: Result<()>, expected: ErrorCode) {
+        match result {
+            Err(anchor_lang::error::Error::AnchorError(error)) => {
+                assert_eq!(error.error_code_number, u32::from(expected), "{}", error)
+            }
+            _ => panic!("expected {}", expected),
+        }
+    }
+
+    #[test]
+    fn accepts_a_valid_sale() {
+        assert!(validate_sale(&sale(), None, NOW).is_ok());
+    }
+
+    #[test]
+    fn accepts_unlimited_sale() {
+        let unlimited = SaleConfig {
+            end_at: None,
+            num_of_tickets: None,
+            ticket_per_wallet: None,
+            ..sale()
+        };
+        assert!(validate_sale(&unlimited, None, NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_start_in_the_past_on_creation() {
+        let sale = SaleConfig {
+            start_at: NOW - 1,
+            ..sale()
+        };
+        assert_error(validate_sale(&sale, None, NOW), ErrorCode::InvalidTicketBoxStartAt);
+    }
+
+    #[test]
+    fn accepts_start_now_on_creation() {
+        let sale = SaleConfig {
+            start_at: NOW,
+            ..sale()
+        };
+        assert!(validate_sale(&sale, None, NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_end_not_after_start() {
+        let sale = SaleConfig {
+            end_at: Some(NOW + 10),
+            ..sale()
+        };
+        assert_error(validate_sale(&sale, None, NOW), ErrorCode::InvalidTicketBoxEndAt);
+    }
+
+    #[test]
+    fn rejects_start_moved_after_end() {
+        let previous = sale();
+        let next = SaleConfig {
+            start_at: NOW + 200,
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketBoxEndAt,
+        );
+    }
+
+    #[test]
+    fn moves_start_before_the_sale() {
+        let previous = sale();
+        let next = SaleConfig {
+            start_at: NOW + 50,
+            ..previous.clone()
+        };
+        assert!(validate_sale(&next, Some(&previous), NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_start_moved_into_the_past() {
+        let previous = sale();
+        let next = SaleConfig {
+            start_at: NOW - 1,
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketBoxStartAt,
+        );
+    }
+
+    #[test]
+    fn rejects_start_moved_once_the_sale_opened() {
+        let previous = SaleConfig {
+            num_of_sold: 0,
+            ..live_sale()
+        };
+        let next = SaleConfig {
+            start_at: NOW + 50,
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketBoxStartAt,
+        );
+    }
+
+    #[test]
+    fn rejects_start_moved_after_a_sale() {
+        let previous = SaleConfig {
+            num_of_sold: 1,
+            ..sale()
+        };
+        let next = SaleConfig {
+            start_at: NOW + 50,
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketBoxStartAt,
+        );
+    }
+
+    #[test]
+    fn keeps_a_past_start_on_update() {
+        let previous = live_sale();
+        let next = SaleConfig {
+            price: 2_000,
+            ..previous.clone()
+        };
+        assert!(validate_sale(&next, Some(&previous), NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_end_moved_into_the_past() {
+        let previous = live_sale();
+        let next = SaleConfig {
+            end_at: Some(NOW - 1),
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketBoxEndAt,
+        );
+    }
+
+    #[test]
+    fn rejects_a_passed_end_moved_back_into_the_future() {
+        let previous = SaleConfig {
+            end_at: Some(NOW),
+            ..live_sale()
+        };
+        let next = SaleConfig {
+            end_at: Some(NOW + 100),
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketBoxEndAt,
+        );
+    }
+
+    #[test]
+    fn rejects_a_passed_end_cleared() {
+        let previous = SaleConfig {
+            end_at: Some(NOW - 1),
+            ..live_sale()
+        };
+        let next = SaleConfig {
+            end_at: None,
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketBoxEndAt,
+        );
+    }
+
+    #[test]
+    fn rejects_any_change_after_a_withdrawal() {
+        let previous = SaleConfig {
+            end_at: Some(NOW - 1),
+            withdrawn: true,
+            ..live_sale()
+        };
+        let next = SaleConfig {
+            num_of_tickets: Some(200),
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::ProceedsAlreadyWithdrawn,
+        );
+        assert!(validate_sale(&previous, Some(&previous), NOW).is_ok());
+    }
+
+    #[test]
+    fn clears_the_end() {
+        let previous = live_sale();
+        let next = SaleConfig {
+            end_at: None,
+            ..previous.clone()
+        };
+        assert!(validate_sale(&next, Some(&previous), NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_price_change_after_the_sale() {
+        let previous = SaleConfig {
+            end_at: Some(NOW),
+            ..live_sale()
+        };
+        let next = SaleConfig {
+            price: 2_000,
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidTicketPrice,
+        );
+    }
+
+    #[test]
+    fn rejects_zero_supply() {
+        let sale = SaleConfig {
+            num_of_tickets: Some(0),
+            ticket_per_wallet: None,
+            ..sale()
+        };
+        assert_error(validate_sale(&sale, None, NOW), ErrorCode::InvalidNumOfTickets);
+    }
+
+    #[test]
+    fn rejects_supply_below_sold() {
+        let previous = live_sale();
+        let next = SaleConfig {
+            num_of_tickets: Some(4),
+            ticket_per_wallet: Some(1),
+            ..previous.clone()
+        };
+        assert_error(
+            validate_sale(&next, Some(&previous), NOW),
+            ErrorCode::InvalidNumOfTickets,
+        );
+    }
+
+    #[test]
+    fn accepts_supply_equal_to_sold() {
+        let previous = live_sale();
+        let next = SaleConfig {
+            num_of_tickets: Some(5),
+            ticket_per_wallet: Some(5),
+            ..previous.clone()
+        };
+        assert!(validate_sale(&next, Some(&previous), NOW).is_ok());
+    }
+
+    #[test]
+    fn rejects_zero_per_wallet() {
+        let sale = SaleConfig {
+            ticket_per_wallet: Some(0),
+            ..sale()
+        };
+        assert_error(validate_sale(&sale, None, NOW), ErrorCode::InvalidTicketPerWallet);
+    }
+
+    #[test]
+    fn rejects_per_wallet_above_supply() {
+        let sale = SaleConfig {
+            ticket_per_wallet: Some(101),
+            ..sale()
+        };
+        assert_error(validate_sale(&sale, None, NOW), ErrorCode::InvalidTicketPerWallet);
+    }
+
+    #[test]
+    fn accepts_per_wallet_equal_to_supply() {
+        let sale = SaleConfig {
+            ticket_per_wallet: Some(100),
+            ..sale()
+        };
+        assert!(validate_sale(&sale, None, NOW).is_ok());
+    }
+
+    fn creator(share: u8) -> TicketBoxCreator {
+        TicketBoxCreator {
+            address: Pubkey::new_unique(),
+            share,
+        }
+    }
+
+    #[test]
+    fn accepts_split_royalties() {
+        assert!(validate_royalties(500, &[creator(70), creator(30)]).is_ok());
+    }
+
+    #[test]
+    fn rejects_seller_fee_above_100_percent() {
+        assert_error(
+            validate_royalties(10_001, &[creator(100)]),
+            ErrorCode::InvalidSellerFeeBasisPoints,
+        );
+    }
+
+    #[test]
+    fn rejects_shares_not_summing_to_100() {
+        assert_error(
+            validate_royalties(500, &[creator(70), creator(20)]),
+            ErrorCode::InvalidCreators,
+        );
+    }
+
+    #[test]
+    fn rejects_too_many_creators() {
+        let creators = [creator(20), creator(20), creator(20), creator(20), creator(20)];
+        assert_error(validate_royalties(500, &creators), ErrorCode::InvalidCreators);
+    }
+
+    #[test]
+    fn accepts_a_name_format_within_the_limit() {
+        // "Flip Girl #0001 #100" is 20 characters
+        let result = validate_naming("Flip Girl #0001", "TICKET", "{name} #{serial}", Some(100));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn rejects_a_name_format_too_long_for_the_last_serial() {
+        assert_error(
+            validate_naming(&"n".repeat(28), "TICKET", "{name} #{serial}", Some(100)),
+            ErrorCode::NameFormatTooLong,
+        );
+    }
+
+    #[test]
+    fn checks_unlimited_boxes_against_a_ten_digit_serial() {
+        let name = "n".repeat(21);
+        assert!(validate_naming(&name, "TICKET", "{name} #{serial}", Some(100)).is_ok());
+        assert_error(
+            validate_naming(&name, "TICKET", "{name} #{serial}", None),
+            ErrorCode::NameFormatTooLong,
+        );
+    }
+
+    #[test]
+    fn rejects_a_symbol_over_10_characters() {
+        assert_error(
+            validate_naming("Concert", "TICKETSTUBS", "{name} #{serial}", Some(100)),
+            ErrorCode::SymbolTooLong,
+        );
+    }
+
+    fn template_uri(len: usize) -> String {
+        let uri = "https://example.com/{serial}.json";
+        format!("{}{}", "a".repeat(len - uri.len()), uri)
+    }
+
+    #[test]
+    fn accepts_a_template_uri_within_the_limit() {
+        // an 8 digit serial takes the place of the 8 byte placeholder
+        let uri = template_uri(TicketBox::MAX_URI_LEN);
+        assert!(validate_uri(&uri, UriMode::Template, Some(99_999_999)).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_template_uri_too_long_for_the_last_serial() {
+        let uri = template_uri(TicketBox::MAX_URI_LEN);
+        assert_error(
+            validate_uri(&uri, UriMode::Template, Some(100_000_000)),
+            ErrorCode::UriTooLong,
+        );
+    }
+
+    #[test]
+    fn checks_unlimited_template_uris_against_a_ten_digit_serial() {
+        let uri = template_uri(TicketBox::MAX_URI_LEN - 1);
+        assert!(validate_uri(&uri, UriMode::Template, Some(100)).is_ok());
+        assert_error(
+            validate_uri(&uri, UriMode::Template, None),
+            ErrorCode::UriTooLong,
+        );
+    }
+
+    #[test]
+    fn ignores_the_placeholder_in_shared_uris() {
+        let uri = template_uri(TicketBox::MAX_URI_LEN);
+        assert!(validate_uri(&uri, UriMode::Shared, None).is_ok());
+        assert_error(
+            validate_uri(&format!("a{}", uri), UriMode::Shared, None),
+            ErrorCode::UriTooLong,
+        );
+    }
+}
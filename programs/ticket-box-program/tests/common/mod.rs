@@ -0,0 +1,215 @@
+//! Shared harness for the `solana-program-test` suites.
+//!
+//! The programs run as BPF: build with `anchor build`, dump Metaplex next to it with
+//! `solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s target/deploy/mpl_token_metadata.so`
+//! then run `cargo test-bpf`.
+
+#![allow(dead_code)]
+
+use anchor_lang::{
+    prelude::*, system_program, AccountDeserialize, AccountSerialize, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::token::spl_token;
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("ticket_box_program", ticket_box_program::id(), None);
+    program_test.add_program("mpl_token_metadata", mpl_token_metadata::id(), None);
+    program_test
+}
+
+/// Funded system account, to sign as a box creator or buyer.
+pub fn add_wallet(program_test: &mut ProgramTest, wallet: &Keypair) {
+    program_test.add_account(
+        wallet.pubkey(),
+        Account {
+            lamports: 100_000_000_000,
+            owner: system_program::ID,
+            ..Account::default()
+        },
+    );
+}
+
+/// Rent-exempt account owned by the program holding `data`.
+pub fn add_program_account(program_test: &mut ProgramTest, address: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: ticket_box_program::id(),
+            ..Account::default()
+        },
+    );
+}
+
+/// Rent-exempt account owned by the program holding `account`, for accounts the tests can't
+/// create through the program, like the upgrade-authority gated config.
+pub fn add_anchor_account<T: AccountSerialize>(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    account: &T,
+    size: usize,
+) {
+    let mut data = Vec::with_capacity(size);
+    account.try_serialize(&mut data).unwrap();
+    data.resize(size, 0);
+    add_program_account(program_test, address, data);
+}
+
+/// Moves the cluster clock, programs read `unix_timestamp` from it.
+pub async fn set_unix_timestamp(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+/// Asserts `result` failed with the program error `expected`.
+pub fn assert_program_error(
+    result: std::result::Result<(), BanksClientError>,
+    expected: ticket_box_program::errors::ErrorCode,
+) {
+    match result.expect_err("transaction succeeded").unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, u32::from(expected), "expected {}", expected)
+        }
+        err => panic!("expected {}, got {}", expected, err),
+    }
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+pub fn program_instruction(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: ticket_box_program::id(),
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub async fn get_account<T: AccountDeserialize>(
+    context: &mut ProgramTestContext,
+    address: Pubkey,
+) -> T {
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .expect("account not found");
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Instructions creating `mint` with a single token held by `owner`, as the clients do
+/// for the collection and ticket NFTs.
+pub fn create_nft_mint(
+    owner: &Pubkey,
+    mint: &Keypair,
+    token_account: &Keypair,
+) -> Vec<Instruction> {
+    let rent = Rent::default();
+    vec![
+        system_instruction::create_account(
+            owner,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::ID,
+            &mint.pubkey(),
+            owner,
+            Some(owner),
+            0,
+        )
+        .unwrap(),
+        system_instruction::create_account(
+            owner,
+            &token_account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_account(
+            &spl_token::ID,
+            &token_account.pubkey(),
+            &mint.pubkey(),
+            owner,
+        )
+        .unwrap(),
+        spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &mint.pubkey(),
+            &token_account.pubkey(),
+            owner,
+            &[],
+            1,
+        )
+        .unwrap(),
+    ]
+}
+
+pub fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+        ],
+        &mpl_token_metadata::id(),
+    )
+    .0
+}
+
+pub fn master_edition_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            mpl_token_metadata::id().as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &mpl_token_metadata::id(),
+    )
+    .0
+}
+
+pub fn ticket_box_pda(uuid: &str, creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"ticket_box", uuid.as_bytes(), creator.as_ref()],
+        &ticket_box_program::id(),
+    )
+    .0
+}
+
+pub fn vault_pda(ticket_box: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", ticket_box.as_ref()], &ticket_box_program::id()).0
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use anchor_lang::{prelude::*, solana_program::sysvar, system_program, Discriminator};
+use anchor_spl::{associated_token, token};
+use common::*;
+use solana_program_test::{tokio, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+use ticket_box_program::{accounts, format_ticket_name, instruction, validate_naming};
+use ticket_box_program::{TicketBox, TicketBoxV0};
+
+/// An unversioned box of `creator`, with a uri longer than Metaplex takes.
+fn unversioned_box(creator: &Keypair) -> TicketBoxV0 {
+    TicketBoxV0 {
+        uuid: "unversioned".to_string(),
+        creator: creator.pubkey(),
+        name: "Unversioned".to_string(),
+        uri: format!("https://example.com/{}", "a".repeat(TicketBox::MAX_URI_LEN)),
+        start_at: 0,
+        end_at: None,
+        num_of_tickets: Some(100),
+        num_of_sold: 3,
+        ticket_per_wallet: None,
+        currency: None,
+        price: 1_000_000,
+        transferable: true,
+        escrow: creator.pubkey(),
+    }
+}
+
+/// Writes `ticket_box_v0` the way the unversioned program did, no version byte and exactly
+/// `SIZE` bytes, then migrates it with a fresh collection mint.
+async fn migrate(
+    creator: &Keypair,
+    ticket_box_v0: &TicketBoxV0,
+) -> (ProgramTestContext, TicketBox, Pubkey) {
+    let ticket_box = ticket_box_pda(&ticket_box_v0.uuid, &creator.pubkey());
+    let mut data = TicketBox::discriminator().to_vec();
+    ticket_box_v0.serialize(&mut data).unwrap();
+    data.resize(TicketBoxV0::SIZE, 0);
+
+    let mut program_test = program_test();
+    add_wallet(&mut program_test, creator);
+    add_program_account(&mut program_test, ticket_box, data);
+    let mut context = program_test.start_with_context().await;
+
+    let collection_mint = Keypair::new();
+    let collection_token_account = Keypair::new();
+    let mut instructions = create_nft_mint(
+        &creator.pubkey(),
+        &collection_mint,
+        &collection_token_account,
+    );
+    instructions.push(program_instruction(
+        accounts::MigrateTicketBox {
+            creator: creator.pubkey(),
+            ticket_box,
+            vault: vault_pda(&ticket_box),
+            collection_mint: collection_mint.pubkey(),
+            collection_metadata: metadata_pda(&collection_mint.pubkey()),
+            collection_master_edition: master_edition_pda(&collection_mint.pubkey()),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::id(),
+        },
+        instruction::MigrateTicketBox {},
+    ));
+    process(
+        &mut context,
+        &instructions,
+        &[creator, &collection_mint, &collection_token_account],
+    )
+    .await
+    .unwrap();
+
+    let migrated = get_account(&mut context, ticket_box).await;
+    (context, migrated, collection_mint.pubkey())
+}
+
+#[tokio::test]
+async fn migrates_an_unversioned_box() {
+    let creator = Keypair::new();
+    let ticket_box_v0 = unversioned_box(&creator);
+    let (mut context, migrated, collection_mint) = migrate(&creator, &ticket_box_v0).await;
+
+    let ticket_box = ticket_box_pda(&ticket_box_v0.uuid, &creator.pubkey());
+    assert_eq!(migrated.version, TicketBox::VERSION);
+    assert_eq!(migrated.uuid, ticket_box_v0.uuid);
+    assert_eq!(migrated.creator, creator.pubkey());
+    assert_eq!(migrated.name, ticket_box_v0.name);
+    assert_eq!(migrated.uri, "");
+    assert_eq!(migrated.num_of_tickets, Some(100));
+    assert_eq!(migrated.num_of_sold, 3);
+    assert_eq!(migrated.price, 1_000_000);
+    assert_eq!(migrated.escrow, vault_pda(&ticket_box));
+    assert_eq!(migrated.collection_mint, collection_mint);
+
+    let collection_metadata = context
+        .banks_client
+        .get_account(metadata_pda(&collection_mint))
+        .await
+        .unwrap()
+        .expect("collection metadata not created");
+    assert_eq!(collection_metadata.owner, mpl_token_metadata::id());
+}
+
+#[tokio::test]
+async fn cuts_long_names_to_leave_room_for_the_last_serial() {
+    let creator = Keypair::new();
+
+    // "{name}{serial}" up to serial 100 leaves 29 bytes, the two byte char would cross them
+    let ticket_box_v0 = TicketBoxV0 {
+        name: format!("{}éaaa", "a".repeat(28)),
+        ..unversioned_box(&creator)
+    };
+    let (_, migrated, _) = migrate(&creator, &ticket_box_v0).await;
+    assert_eq!(migrated.name, "a".repeat(28));
+
+    // unlimited boxes leave room for a ten digit serial
+    let ticket_box_v0 = TicketBoxV0 {
+        name: "a".repeat(TicketBox::MAX_NAME_LEN),
+        num_of_tickets: None,
+        ..unversioned_box(&creator)
+    };
+    let (_, migrated, _) = migrate(&creator, &ticket_box_v0).await;
+    assert_eq!(migrated.name, "a".repeat(22));
+    let last_name = format_ticket_name(&migrated.name_format, &migrated.name, u32::MAX as i64);
+    assert_eq!(last_name.len(), TicketBox::MAX_NAME_LEN);
+    assert!(validate_naming(
+        &migrated.name,
+        &migrated.symbol,
+        &migrated.name_format,
+        migrated.num_of_tickets,
+    )
+    .is_ok());
+}
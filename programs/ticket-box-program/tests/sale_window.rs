@@ -0,0 +1,210 @@
+mod common;
+
+use anchor_lang::{prelude::*, solana_program::sysvar, system_program};
+use anchor_spl::{associated_token, token};
+use common::*;
+use solana_program_test::{tokio, BanksClientError, ProgramTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+use ticket_box_program::{accounts, errors::ErrorCode, instruction};
+use ticket_box_program::{Config, TicketBox, TicketBoxCreator, UriMode};
+
+const NOW: i64 = 1_700_000_000;
+const START_AT: i64 = NOW + 100;
+const END_AT: i64 = NOW + 200;
+
+struct SaleWindow {
+    context: ProgramTestContext,
+    creator: Keypair,
+    buyer: Keypair,
+    ticket_box: Pubkey,
+    collection_mint: Pubkey,
+    config: Pubkey,
+    treasury: Pubkey,
+}
+
+/// A free box selling from `START_AT` until `END_AT`, created at `NOW`.
+async fn sale_window() -> SaleWindow {
+    let creator = Keypair::new();
+    let buyer = Keypair::new();
+    let treasury = Pubkey::new_unique();
+    let (config, config_bump) =
+        Pubkey::find_program_address(&[b"config"], &ticket_box_program::id());
+
+    let mut program_test = program_test();
+    add_wallet(&mut program_test, &creator);
+    add_wallet(&mut program_test, &buyer);
+    // `initialize_config` needs the upgradeable loader, seed the fee-less config instead
+    add_anchor_account(
+        &mut program_test,
+        config,
+        &Config {
+            admin: creator.pubkey(),
+            treasury,
+            fee_bps: 0,
+            bump: config_bump,
+        },
+        Config::SIZE,
+    );
+    let mut context = program_test.start_with_context().await;
+    set_unix_timestamp(&mut context, NOW).await;
+
+    let uuid = "sale-window";
+    let ticket_box = ticket_box_pda(uuid, &creator.pubkey());
+    let collection_mint = Keypair::new();
+    let collection_token_account = Keypair::new();
+    let mut instructions = create_nft_mint(
+        &creator.pubkey(),
+        &collection_mint,
+        &collection_token_account,
+    );
+    instructions.push(program_instruction(
+        accounts::Initialize {
+            creator: creator.pubkey(),
+            ticket_box,
+            vault: vault_pda(&ticket_box),
+            collection_mint: collection_mint.pubkey(),
+            collection_token_account: collection_token_account.pubkey(),
+            collection_metadata: metadata_pda(&collection_mint.pubkey()),
+            collection_master_edition: master_edition_pda(&collection_mint.pubkey()),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: mpl_token_metadata::id(),
+        },
+        instruction::Initialize {
+            uuid: uuid.to_string(),
+            name: "Sale window".to_string(),
+            uri: "https://example.com/ticket.json".to_string(),
+            start_at: START_AT,
+            end_at: Some(END_AT),
+            num_of_tickets: Some(100),
+            ticket_per_wallet: Some(10),
+            price: 0,
+            transferable: true,
+            seller_fee_basis_points: 0,
+            creators: vec![TicketBoxCreator {
+                address: creator.pubkey(),
+                share: 100,
+            }],
+            uri_mode: UriMode::Shared,
+            symbol: TicketBox::DEFAULT_SYMBOL.to_string(),
+            name_format: TicketBox::DEFAULT_NAME_FORMAT.to_string(),
+        },
+    ));
+    process(
+        &mut context,
+        &instructions,
+        &[&creator, &collection_mint, &collection_token_account],
+    )
+    .await
+    .unwrap();
+
+    SaleWindow {
+        context,
+        creator,
+        buyer,
+        ticket_box,
+        collection_mint: collection_mint.pubkey(),
+        config,
+        treasury,
+    }
+}
+
+impl SaleWindow {
+    async fn mint(&mut self) -> std::result::Result<(), BanksClientError> {
+        let ticket_mint = Keypair::new();
+        let ticket_token_account = Keypair::new();
+        // the client creates the ticket mint, one transaction can't also fit the mint
+        process(
+            &mut self.context,
+            &create_nft_mint(&self.buyer.pubkey(), &ticket_mint, &ticket_token_account),
+            &[&self.buyer, &ticket_mint, &ticket_token_account],
+        )
+        .await
+        .unwrap();
+
+        let ticket_box = self.ticket_box;
+        let mint_instruction = program_instruction(
+            accounts::MintTicket {
+                payer: self.buyer.pubkey(),
+                ticket_box,
+                purchase_record: Pubkey::find_program_address(
+                    &[
+                        b"purchase_record",
+                        ticket_box.as_ref(),
+                        self.buyer.pubkey().as_ref(),
+                    ],
+                    &ticket_box_program::id(),
+                )
+                .0,
+                wallet: vault_pda(&ticket_box),
+                config: self.config,
+                treasury: self.treasury,
+                ticket_mint: ticket_mint.pubkey(),
+                ticket: Pubkey::find_program_address(
+                    &[
+                        b"ticket",
+                        ticket_box.as_ref(),
+                        ticket_mint.pubkey().as_ref(),
+                    ],
+                    &ticket_box_program::id(),
+                )
+                .0,
+                ticket_token_account: ticket_token_account.pubkey(),
+                ticket_metadata: metadata_pda(&ticket_mint.pubkey()),
+                ticket_master_edition: master_edition_pda(&ticket_mint.pubkey()),
+                collection_mint: self.collection_mint,
+                collection_metadata: metadata_pda(&self.collection_mint),
+                collection_master_edition: master_edition_pda(&self.collection_mint),
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                token_metadata_program: mpl_token_metadata::id(),
+            },
+            instruction::Mint {
+                uri: String::new(),
+                max_price: 0,
+                currency: None,
+            },
+        );
+        process(
+            &mut self.context,
+            &[mint_instruction],
+            &[&self.buyer, &ticket_mint],
+        )
+        .await
+    }
+}
+
+#[tokio::test]
+async fn rejects_mints_before_start_at() {
+    let mut sale = sale_window().await;
+
+    set_unix_timestamp(&mut sale.context, START_AT - 1).await;
+    assert_program_error(sale.mint().await, ErrorCode::SaleNotStarted);
+}
+
+#[tokio::test]
+async fn mints_within_the_sale_window() {
+    let mut sale = sale_window().await;
+
+    set_unix_timestamp(&mut sale.context, START_AT).await;
+    sale.mint().await.unwrap();
+
+    set_unix_timestamp(&mut sale.context, END_AT - 1).await;
+    sale.mint().await.unwrap();
+
+    let ticket_box: TicketBox = get_account(&mut sale.context, sale.ticket_box).await;
+    assert_eq!(ticket_box.num_of_sold, 2);
+    assert_eq!(ticket_box.creator, sale.creator.pubkey());
+}
+
+#[tokio::test]
+async fn rejects_mints_from_end_at() {
+    let mut sale = sale_window().await;
+
+    set_unix_timestamp(&mut sale.context, END_AT).await;
+    assert_program_error(sale.mint().await, ErrorCode::EventEnded);
+}
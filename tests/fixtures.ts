@@ -0,0 +1,487 @@
+import {
+  AnchorProvider,
+  Program,
+  Provider,
+  web3,
+  BN,
+} from "@project-serum/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createAssociatedTokenAccountInstruction,
+  createInitializeMintInstruction,
+  createMint,
+  createMintToInstruction,
+  getAssociatedTokenAddress,
+  mintTo,
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getPDA, sleep } from "./utils";
+import {
+  COLLECTION_ASSET_URL,
+  TICKET_ASSET_URL,
+  TOKEN_DECIMALS,
+  TOKEN_METADATA_PROGRAM_ID,
+} from "./contants";
+
+export type TicketBoxFixture = {
+  id: string;
+  creator: web3.Keypair;
+  pda: web3.PublicKey;
+  vault: web3.PublicKey;
+  collectionMint: web3.PublicKey;
+  collectionMetadata: web3.PublicKey;
+  collectionMasterEdition: web3.PublicKey;
+  startAt: number;
+  endAt: number;
+  price: number;
+  currency?: web3.PublicKey;
+  escrow: web3.PublicKey;
+};
+
+export type TicketBoxOptions = {
+  numOfTickets: number;
+  ticketPerWallet: number;
+  startAt?: number;
+  endAt?: number;
+  price?: number;
+  // currency mint, None for sol
+  currency?: web3.PublicKey;
+  transferable?: boolean;
+  sellerFeeBasisPoints?: number;
+  // royalty recipients, the creator gets 100% when empty
+  creators?: { address: web3.PublicKey; share: number }[];
+  // box uri, a template in `TEMPLATE_URI` mode
+  uri?: string;
+  uriMode?: UriMode;
+  symbol?: string;
+  // ticket NFT name, `{name}` and `{serial}` are filled in at mint
+  nameFormat?: string;
+  // uuid, unique per call when unset
+  id?: string;
+};
+
+export type TicketFixture = {
+  tx: string;
+  pda: web3.PublicKey;
+  mint: web3.PublicKey;
+  tokenAccount: web3.PublicKey;
+  metadata: web3.PublicKey;
+  masterEdition: web3.PublicKey;
+};
+
+// seconds between box creation and the sale opening
+export const SALE_DELAY = 5;
+
+// `OptionUpdate` values for the optional limits of `update`
+export const KEEP = { keep: {} };
+export const CLEAR = { clear: {} };
+export const set = (value: number) => ({ set: { value: new BN(value) } });
+export type OptionUpdate = typeof KEEP | typeof CLEAR | ReturnType<typeof set>;
+
+// `UriMode` values
+export const SHARED_URI = { shared: {} };
+export const TEMPLATE_URI = { template: {} };
+export const CREATOR_SIGNED_URI = { creatorSigned: {} };
+export type UriMode =
+  | typeof SHARED_URI
+  | typeof TEMPLATE_URI
+  | typeof CREATOR_SIGNED_URI;
+
+// `CurrencyUpdate` values for `update`, `KEEP` leaves the currency as is
+export const SOL = { sol: {} };
+export const token = (mint: web3.PublicKey) => ({ token: { mint } });
+
+export const updateAccounts = (
+  box: TicketBoxFixture,
+  authority: web3.PublicKey
+) => ({
+  authority,
+  ticketBox: box.pda,
+  vault: box.vault,
+  systemProgram: web3.SystemProgram.programId,
+  rent: web3.SYSVAR_RENT_PUBKEY,
+  tokenProgram: TOKEN_PROGRAM_ID,
+  associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+  collectionMetadata: box.collectionMetadata,
+  tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+});
+
+const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new web3.PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+export const getConfigPDA = (program: Program<TicketBoxProgram>) =>
+  getPDA([Buffer.from("config")], program.programId);
+
+// creates the fee-less platform config on first use, the provider wallet
+// deployed the program so it is the upgrade authority and the admin
+export const ensureConfig = async (program: Program<TicketBoxProgram>) => {
+  const config = await getConfigPDA(program);
+  const { wallet, connection } = program.provider as AnchorProvider;
+  if (!(await connection.getAccountInfo(config))) {
+    const programData = await getPDA(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_PROGRAM_ID
+    );
+    await program.methods
+      .initializeConfig(wallet.publicKey, wallet.publicKey, 0)
+      .accounts({
+        authority: wallet.publicKey,
+        config,
+        program: program.programId,
+        programData,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+  }
+  const account = await program.account.config.fetch(config);
+  return { pda: config, ...account };
+};
+
+export const waitUntil = async (timestamp: number) => {
+  const now = Math.floor(new Date().getTime() / 1000);
+  if (timestamp >= now) {
+    await sleep(timestamp - now + 1);
+  }
+};
+
+export const getMetadataPDA = (mint: web3.PublicKey) =>
+  getPDA(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+
+export const getMasterEditionPDA = (mint: web3.PublicKey) =>
+  getPDA(
+    [
+      Buffer.from("metadata"),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+      Buffer.from("edition"),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+
+// create a currency mint and fund an ATA for every holder
+export const createCurrency = async (
+  provider: Provider,
+  authority: web3.Keypair,
+  holders: web3.Keypair[],
+  amount: number
+): Promise<web3.PublicKey> => {
+  const currencyMint = await createMint(
+    provider.connection,
+    authority,
+    authority.publicKey,
+    authority.publicKey,
+    TOKEN_DECIMALS
+  );
+
+  for (const holder of holders) {
+    const tokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      holder,
+      currencyMint,
+      holder.publicKey
+    );
+    if (amount > 0) {
+      await mintTo(
+        provider.connection,
+        authority,
+        currencyMint,
+        tokenAccount,
+        authority,
+        amount
+      );
+    }
+  }
+
+  return currencyMint;
+};
+
+// create a 0-decimal mint owned by `owner` and mint 1 token to its ATA
+const createNftMintInstructions = async (
+  program: Program<TicketBoxProgram>,
+  owner: web3.PublicKey,
+  mint: web3.PublicKey
+) => {
+  const ix = [];
+  const lamports: number =
+    await program.provider.connection.getMinimumBalanceForRentExemption(
+      MINT_SIZE
+    );
+
+  ix.push(
+    web3.SystemProgram.createAccount({
+      fromPubkey: owner,
+      newAccountPubkey: mint,
+      space: MINT_SIZE,
+      programId: TOKEN_PROGRAM_ID,
+      lamports,
+    })
+  );
+
+  ix.push(createInitializeMintInstruction(mint, 0, owner, owner));
+
+  const tokenAccount = await getAssociatedTokenAddress(mint, owner);
+  ix.push(
+    createAssociatedTokenAccountInstruction(owner, tokenAccount, owner, mint)
+  );
+
+  ix.push(createMintToInstruction(mint, tokenAccount, owner, 1));
+
+  return { ix, tokenAccount };
+};
+
+export const initTicketBox = async (
+  program: Program<TicketBoxProgram>,
+  creator: web3.Keypair,
+  {
+    numOfTickets,
+    ticketPerWallet,
+    startAt = Math.floor(new Date().getTime() / 1000) + SALE_DELAY,
+    endAt = startAt + 10 * 60 * 1000,
+    price = 0,
+    currency,
+    transferable = true,
+    sellerFeeBasisPoints = 200,
+    creators = [],
+    uri = COLLECTION_ASSET_URL,
+    uriMode = SHARED_URI,
+    symbol = "TICKET",
+    nameFormat = "{name} #{serial}",
+    id = new Date().getTime().toString(),
+  }: TicketBoxOptions
+): Promise<TicketBoxFixture> => {
+  const ticketBoxId = id;
+  const ticketBoxName = "Flip Girl #0001";
+
+  const ticketBoxPda = await getPDA(
+    [
+      Buffer.from("ticket_box"),
+      Buffer.from(ticketBoxId),
+      creator.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+  const vaultPda = await getPDA(
+    [Buffer.from("vault"), ticketBoxPda.toBuffer()],
+    program.programId
+  );
+  const escrow = currency
+    ? await getAssociatedTokenAddress(currency, vaultPda, true)
+    : vaultPda;
+
+  // pre-tx to mint collection
+  const collectionMinKp = web3.Keypair.generate();
+  const { ix, tokenAccount: collectionTokenAccount } =
+    await createNftMintInstructions(
+      program,
+      creator.publicKey,
+      collectionMinKp.publicKey
+    );
+
+  const collectionMetadataPDA = await getMetadataPDA(
+    collectionMinKp.publicKey
+  );
+  const collectionMasterEditionPda = await getMasterEditionPDA(
+    collectionMinKp.publicKey
+  );
+
+  const tx = await program.methods
+    .initialize(
+      ticketBoxId,
+      ticketBoxName,
+      uri,
+      new BN(startAt),
+      new BN(endAt),
+      new BN(numOfTickets),
+      new BN(ticketPerWallet),
+      new BN(price),
+      transferable,
+      sellerFeeBasisPoints,
+      creators,
+      uriMode,
+      symbol,
+      nameFormat
+    )
+    .accounts({
+      creator: creator.publicKey,
+      ticketBox: ticketBoxPda,
+      vault: vaultPda,
+      collectionMint: collectionMinKp.publicKey,
+      collectionTokenAccount: collectionTokenAccount,
+      collectionMetadata: collectionMetadataPDA,
+      collectionMasterEdition: collectionMasterEditionPda,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      rent: web3.SYSVAR_RENT_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    })
+    .remainingAccounts(
+      currency
+        ? [
+            // currency mint
+            { pubkey: currency, isSigner: false, isWritable: false },
+            // vault token account
+            { pubkey: escrow, isSigner: false, isWritable: true },
+          ]
+        : []
+    )
+    .preInstructions(ix)
+    .signers([creator, collectionMinKp])
+    .rpc();
+
+  console.log("Initialize tx hash", tx);
+
+  return {
+    id: ticketBoxId,
+    creator,
+    pda: ticketBoxPda,
+    vault: vaultPda,
+    collectionMint: collectionMinKp.publicKey,
+    collectionMetadata: collectionMetadataPDA,
+    collectionMasterEdition: collectionMasterEditionPda,
+    startAt,
+    endAt,
+    price,
+    currency,
+    escrow,
+  };
+};
+
+export const mintTicket = async (
+  program: Program<TicketBoxProgram>,
+  box: TicketBoxFixture,
+  buyer: web3.Keypair,
+  {
+    collection = box,
+    wallet = box.escrow,
+    tier,
+    maxPrice,
+    currency = box.currency ?? null,
+    uri = TICKET_ASSET_URL,
+    uriSigner,
+  }: {
+    collection?: TicketBoxFixture;
+    wallet?: web3.PublicKey;
+    // ticket tier PDA, required once the box has tiers
+    tier?: web3.PublicKey;
+    // defaults to the current tier or box price
+    maxPrice?: number;
+    currency?: web3.PublicKey | null;
+    uri?: string;
+    // box creator approving `uri` for `CreatorSigned` boxes
+    uriSigner?: web3.Keypair;
+  } = {}
+): Promise<TicketFixture> => {
+  if (maxPrice === undefined) {
+    const { price } = tier
+      ? await program.account.ticketTier.fetch(tier)
+      : await program.account.ticketBox.fetch(box.pda);
+    maxPrice = price.toNumber();
+  }
+  const config = await ensureConfig(program);
+
+  const ticketMinKp = web3.Keypair.generate();
+  const { ix, tokenAccount: ticketTokenAccount } =
+    await createNftMintInstructions(
+      program,
+      buyer.publicKey,
+      ticketMinKp.publicKey
+    );
+
+  const metadataPDA = await getMetadataPDA(ticketMinKp.publicKey);
+  const masterEditionPda = await getMasterEditionPDA(ticketMinKp.publicKey);
+
+  const purchaseRecordPda = await getPDA(
+    [
+      Buffer.from("purchase_record"),
+      box.pda.toBuffer(),
+      buyer.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+
+  const ticketPda = await getPDA(
+    [
+      Buffer.from("ticket"),
+      box.pda.toBuffer(),
+      ticketMinKp.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+
+  const tx = await program.methods
+    .mint(uri, new BN(maxPrice), currency)
+    .accounts({
+      payer: buyer.publicKey,
+      ticketBox: box.pda,
+      purchaseRecord: purchaseRecordPda,
+      wallet,
+      config: config.pda,
+      treasury: config.treasury,
+      ticketMint: ticketMinKp.publicKey,
+      ticket: ticketPda,
+      ticketTokenAccount: ticketTokenAccount,
+      ticketMetadata: metadataPDA,
+      ticketMasterEdition: masterEditionPda,
+      collectionMint: collection.collectionMint,
+      collectionMetadata: collection.collectionMetadata,
+      collectionMasterEdition: collection.collectionMasterEdition,
+      systemProgram: web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      rent: web3.SYSVAR_RENT_PUBKEY,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+    })
+    .remainingAccounts([
+      ...(tier ? [{ pubkey: tier, isSigner: false, isWritable: true }] : []),
+      ...(uriSigner
+        ? [{ pubkey: uriSigner.publicKey, isSigner: true, isWritable: false }]
+        : []),
+      ...(box.currency && box.price > 0
+        ? [
+            // buyer token account
+            {
+              pubkey: await getAssociatedTokenAddress(
+                box.currency,
+                buyer.publicKey
+              ),
+              isSigner: false,
+              isWritable: true,
+            },
+            // transfer authority
+            { pubkey: buyer.publicKey, isSigner: true, isWritable: false },
+            // treasury token account, used when a fee is due
+            {
+              pubkey: await getAssociatedTokenAddress(
+                box.currency,
+                config.treasury
+              ),
+              isSigner: false,
+              isWritable: true,
+            },
+          ]
+        : []),
+    ])
+    .preInstructions(ix)
+    .signers([buyer, ticketMinKp, ...(uriSigner ? [uriSigner] : [])])
+    .rpc();
+
+  return {
+    tx,
+    pda: ticketPda,
+    mint: ticketMinKp.publicKey,
+    tokenAccount: ticketTokenAccount,
+    metadata: metadataPDA,
+    masterEdition: masterEditionPda,
+  };
+};
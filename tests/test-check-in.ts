@@ -0,0 +1,80 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import {
+  initTicketBox,
+  mintTicket,
+  TicketBoxFixture,
+  TicketFixture,
+  waitUntil,
+} from "./fixtures";
+
+describe("Check in", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  let creator: web3.Keypair;
+  let buyer: web3.Keypair;
+  let ticketBox: TicketBoxFixture;
+  let ticket: TicketFixture;
+
+  const checkIn = (authority: web3.Keypair) =>
+    program.methods
+      .checkIn()
+      .accounts({
+        authority: authority.publicKey,
+        ticketBox: ticketBox.pda,
+        ticketMint: ticket.mint,
+        ticketTokenAccount: ticket.tokenAccount,
+        ticket: ticket.pda,
+      })
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+
+    ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+    });
+    await waitUntil(ticketBox.startAt);
+    ticket = await mintTicket(program, ticketBox, buyer);
+  });
+
+  it("Rejects check in by an unrelated signer", async () => {
+    try {
+      await checkIn(buyer);
+      assert.fail("buyer checked in their own ticket");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("Checks in a ticket once", async () => {
+    await checkIn(creator);
+
+    const loadedTicketAccount = await program.account.ticket.fetch(
+      ticket.pda
+    );
+    assert.isNotNull(loadedTicketAccount.checkedInAt, "checked_in_at");
+
+    try {
+      await checkIn(creator);
+      assert.fail("checked in twice");
+    } catch (err) {
+      assert.include(err.toString(), "AlreadyCheckedIn");
+    }
+  });
+});
@@ -0,0 +1,179 @@
+import {
+  workspace,
+  Program,
+  web3,
+  BN,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getPDA, getSolBalance, handleAirdrop } from "./utils";
+import { TICKET_ASSET_URL } from "./contants";
+import {
+  initTicketBox,
+  mintTicket,
+  SALE_DELAY,
+  TicketBoxFixture,
+  waitUntil,
+} from "./fixtures";
+
+describe("Close ticket box", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  let creator: web3.Keypair;
+
+  const addTier = (box: TicketBoxFixture, tier: web3.PublicKey) =>
+    program.methods
+      .addTier(
+        "GA",
+        TICKET_ASSET_URL,
+        new BN(box.startAt),
+        null,
+        null,
+        null,
+        new BN(0)
+      )
+      .accounts({
+        creator: creator.publicKey,
+        ticketBox: box.pda,
+        ticketTier: tier,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+  const getTierPDA = (box: TicketBoxFixture, index: number) =>
+    getPDA(
+      [
+        Buffer.from("ticket_tier"),
+        box.pda.toBuffer(),
+        Buffer.from([index]),
+      ],
+      program.programId
+    );
+
+  const closeTicketBox = (
+    box: TicketBoxFixture,
+    related: web3.PublicKey[] = []
+  ) =>
+    program.methods
+      .closeTicketBox()
+      .accounts({
+        creator: creator.publicKey,
+        ticketBox: box.pda,
+        vault: box.vault,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        related.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .signers([creator])
+      .rpc();
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+  });
+
+  it("Closes an unsold box with its tiers and vault", async () => {
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+    });
+    const tier = await getTierPDA(ticketBox, 0);
+    await addTier(ticketBox, tier);
+
+    await closeTicketBox(ticketBox, [tier]);
+
+    for (const pubkey of [ticketBox.pda, ticketBox.vault, tier]) {
+      assert.isNull(await provider.connection.getAccountInfo(pubkey));
+    }
+  });
+
+  it("Takes over tiers left by a closed box of the same uuid", async () => {
+    const id = new Date().getTime().toString();
+    const options = { numOfTickets: 100, ticketPerWallet: 10, id };
+    const closedBox = await initTicketBox(program, creator, options);
+    const tier = await getTierPDA(closedBox, 0);
+    await addTier(closedBox, tier);
+    // the tier isn't passed, so it outlives the box
+    await closeTicketBox(closedBox);
+    assert.isNotNull(await provider.connection.getAccountInfo(tier));
+
+    const ticketBox = await initTicketBox(program, creator, options);
+    assert.isTrue(ticketBox.pda.equals(closedBox.pda));
+    await addTier(ticketBox, tier);
+    const loadedTier = await program.account.ticketTier.fetch(tier);
+    assert.isTrue(
+      loadedTier.collectionMint.equals(ticketBox.collectionMint),
+      "collection mint"
+    );
+  });
+
+  it("Refunds purchase record rent to the buyer", async () => {
+    const now = Math.floor(new Date().getTime() / 1000);
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      startAt: now + SALE_DELAY,
+      endAt: now + 2 * SALE_DELAY,
+    });
+    await waitUntil(ticketBox.startAt);
+
+    const buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, buyer.publicKey);
+    await mintTicket(program, ticketBox, buyer);
+    const purchaseRecord = await getPDA(
+      [
+        Buffer.from("purchase_record"),
+        ticketBox.pda.toBuffer(),
+        buyer.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    await waitUntil(ticketBox.endAt);
+
+    // each purchase record is followed by its buyer
+    try {
+      await closeTicketBox(ticketBox, [purchaseRecord, creator.publicKey]);
+      assert.fail("sent the purchase record rent to the creator");
+    } catch (err) {
+      assert.include(err.toString(), "PublicKeyMismatch");
+    }
+
+    const rent = await getSolBalance(purchaseRecord, provider);
+    const buyerBalanceBefore = await getSolBalance(buyer.publicKey, provider);
+    await closeTicketBox(ticketBox, [purchaseRecord, buyer.publicKey]);
+    const buyerBalanceAfter = await getSolBalance(buyer.publicKey, provider);
+    assert.strictEqual(buyerBalanceAfter - buyerBalanceBefore, rent);
+    assert.isNull(await provider.connection.getAccountInfo(purchaseRecord));
+  });
+
+  it("Keeps a box with sales open until the event ends", async () => {
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+    });
+    await waitUntil(ticketBox.startAt);
+
+    const buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, buyer.publicKey);
+    await mintTicket(program, ticketBox, buyer);
+
+    try {
+      await closeTicketBox(ticketBox);
+      assert.fail("closed a box during the sale");
+    } catch (err) {
+      assert.include(err.toString(), "TicketBoxNotClosable");
+    }
+  });
+});
@@ -0,0 +1,143 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { getAssociatedTokenAddress } from "@solana/spl-token";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getSolBalance, getTokenBalance, handleAirdrop } from "./utils";
+import { TOKEN_DECIMALS } from "./contants";
+import {
+  createCurrency,
+  ensureConfig,
+  initTicketBox,
+  mintTicket,
+  waitUntil,
+} from "./fixtures";
+
+describe("Config", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  const feeBps = 500;
+
+  let admin: web3.PublicKey;
+  let config: web3.PublicKey;
+  let creator: web3.Keypair;
+  let buyer: web3.Keypair;
+  let treasury: web3.Keypair;
+
+  const updateConfig = (
+    newTreasury: web3.PublicKey | null,
+    newFeeBps: number | null
+  ) =>
+    program.methods
+      .updateConfig(null, newTreasury, newFeeBps)
+      .accounts({ admin, config })
+      .rpc();
+
+  before(async () => {
+    ({ admin, pda: config } = await ensureConfig(program));
+    creator = web3.Keypair.generate();
+    buyer = web3.Keypair.generate();
+    treasury = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+    await handleAirdrop(provider, treasury.publicKey);
+
+    await updateConfig(treasury.publicKey, feeBps);
+  });
+
+  after(async () => {
+    // other suites expect the full price in the escrow
+    await updateConfig(admin, 0);
+  });
+
+  it("Only lets the admin update the config", async () => {
+    const other = web3.Keypair.generate();
+    try {
+      await program.methods
+        .updateConfig(other.publicKey, null, null)
+        .accounts({ admin: other.publicKey, config })
+        .signers([other])
+        .rpc();
+      assert.fail("updated the config as another signer");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+
+    try {
+      await updateConfig(null, 10001);
+      assert.fail("set a fee over 100%");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidFeeBps");
+    }
+  });
+
+  it("Splits SOL payments with the treasury", async () => {
+    const price = 0.1 * web3.LAMPORTS_PER_SOL;
+    const fee = (price * feeBps) / 10000;
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      price,
+    });
+    await waitUntil(ticketBox.startAt);
+
+    const escrowBalanceBefore = await getSolBalance(ticketBox.escrow, provider);
+    const treasuryBalanceBefore = await getSolBalance(
+      treasury.publicKey,
+      provider
+    );
+    const ticket = await mintTicket(program, ticketBox, buyer);
+    const escrowBalanceAfter = await getSolBalance(ticketBox.escrow, provider);
+    const treasuryBalanceAfter = await getSolBalance(
+      treasury.publicKey,
+      provider
+    );
+    assert.strictEqual(escrowBalanceAfter - escrowBalanceBefore, price - fee);
+    assert.strictEqual(treasuryBalanceAfter - treasuryBalanceBefore, fee);
+
+    const loadedTicketAccount = await program.account.ticket.fetch(
+      ticket.pda
+    );
+    assert.strictEqual(loadedTicketAccount.protocolFee.toNumber(), fee);
+  });
+
+  it("Splits SPL payments with the treasury", async () => {
+    const price = 5 * 10 ** TOKEN_DECIMALS;
+    const fee = (price * feeBps) / 10000;
+    const currencyMint = await createCurrency(
+      provider,
+      creator,
+      [buyer, treasury],
+      10 * price
+    );
+    const treasuryTokenAccount = await getAssociatedTokenAddress(
+      currencyMint,
+      treasury.publicKey
+    );
+
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      price,
+      currency: currencyMint,
+    });
+    await waitUntil(ticketBox.startAt);
+
+    await mintTicket(program, ticketBox, buyer);
+    assert.strictEqual(
+      await getTokenBalance(ticketBox.escrow, provider),
+      price - fee
+    );
+    assert.strictEqual(
+      await getTokenBalance(treasuryTokenAccount, provider),
+      10 * price + fee
+    );
+  });
+});
@@ -0,0 +1,51 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  EventParser,
+  setProvider,
+} from "@project-serum/anchor";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import { initTicketBox, mintTicket, waitUntil } from "./fixtures";
+
+describe("Events", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  const getEvents = async (tx: string) => {
+    await provider.connection.confirmTransaction(tx, "confirmed");
+    const { meta } = await provider.connection.getTransaction(tx, {
+      commitment: "confirmed",
+    });
+    const parser = new EventParser(program.programId, program.coder);
+    return [...parser.parseLogs(meta.logMessages)];
+  };
+
+  it("Emits TicketMinted", async () => {
+    const creator = web3.Keypair.generate();
+    const buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+    });
+    await waitUntil(ticketBox.startAt);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+
+    const events = await getEvents(ticket.tx);
+    const minted = events.find((event) => event.name === "TicketMinted");
+    assert.exists(minted, "TicketMinted");
+    const data = minted.data as Record<string, any>;
+    assert.isTrue(data.buyer.equals(buyer.publicKey), "buyer");
+    assert.isTrue(data.ticketMint.equals(ticket.mint), "ticket_mint");
+    assert.strictEqual(data.serial.toNumber(), 1, "serial");
+    assert.strictEqual(data.price.toNumber(), 0, "price");
+    assert.isNull(data.currency, "currency");
+  });
+});
@@ -7,6 +7,7 @@ import {
   setProvider,
 } from "@project-serum/anchor";
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createAssociatedTokenAccount,
   createAssociatedTokenAccountInstruction,
   createInitializeMintInstruction,
@@ -87,6 +88,17 @@ describe("Initialize", () => {
       program.programId
     );
 
+    const vaultPda = await getPDA(
+      [Buffer.from("vault"), ticketBoxPda.toBuffer()],
+      program.programId
+    );
+
+    const vaultTokenAccount = await getAssociatedTokenAddress(
+      currencyMintPubkey,
+      vaultPda,
+      true
+    );
+
     // pre-tx to mint collection
     const ix = [];
     collectionMinKp = web3.Keypair.generate();
@@ -169,18 +181,24 @@ describe("Initialize", () => {
         new BN(1), //
         new BN(0.5 * 10 ** 9),
         // new BN(0),
-        true
+        true,
+        200,
+        [],
+        { shared: {} },
+        "TICKET",
+        "{name} #{serial}"
       )
       .accounts({
         creator: creator.publicKey,
         ticketBox: ticketBoxPda,
-        wallet: creator.publicKey,
+        vault: vaultPda,
         collectionMint: collectionMinKp.publicKey,
         collectionTokenAccount: collectionTokenAccount,
         collectionMetadata: collectionMetadataPDA,
         collectionMasterEdition: collectionMasterEditionPda,
         systemProgram: web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: web3.SYSVAR_RENT_PUBKEY,
         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       })
@@ -191,6 +209,12 @@ describe("Initialize", () => {
           isSigner: false,
           isWritable: false,
         },
+        // vault token account
+        {
+          pubkey: vaultTokenAccount,
+          isSigner: false,
+          isWritable: true,
+        },
       ])
       .preInstructions(ix)
       .signers([creator, collectionMinKp])
@@ -217,5 +241,11 @@ describe("Initialize", () => {
       currencyMintPubkey.toBase58(),
       "creator"
     );
+
+    assert.strictEqual(
+      loadedTicketBoxAccount.escrow.toBase58(),
+      vaultTokenAccount.toBase58(),
+      "escrow"
+    );
   });
 });
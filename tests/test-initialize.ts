@@ -7,6 +7,7 @@ import {
   setProvider,
 } from "@project-serum/anchor";
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createAssociatedTokenAccountInstruction,
   createInitializeMintInstruction,
   createMintToInstruction,
@@ -21,6 +22,7 @@ import {
   COLLECTION_ASSET_URL,
   TOKEN_METADATA_PROGRAM_ID,
 } from "./contants";
+import { getMasterEditionPDA, getMetadataPDA } from "./fixtures";
 
 describe("Initialize", () => {
   const provider = AnchorProvider.env();
@@ -57,6 +59,11 @@ describe("Initialize", () => {
       program.programId
     );
 
+    const vaultPda = await getPDA(
+      [Buffer.from("vault"), ticketBoxPda.toBuffer()],
+      program.programId
+    );
+
     // pre-tx to mint collection
     const ix = [];
     collectionMinKp = web3.Keypair.generate();
@@ -139,18 +146,24 @@ describe("Initialize", () => {
         new BN(1), //
         new BN(0.5 * 10 ** 9), // 0.5 SOL
         // new BN(0),
-        true
+        true,
+        200,
+        [],
+        { shared: {} },
+        "TICKET",
+        "{name} #{serial}"
       )
       .accounts({
         creator: creator.publicKey,
         ticketBox: ticketBoxPda,
-        wallet: creator.publicKey,
+        vault: vaultPda,
         collectionMint: collectionMinKp.publicKey,
         collectionTokenAccount: collectionTokenAccount,
         collectionMetadata: collectionMetadataPDA,
         collectionMasterEdition: collectionMasterEditionPda,
         systemProgram: web3.SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: web3.SYSVAR_RENT_PUBKEY,
         tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
       })
@@ -182,4 +195,59 @@ describe("Initialize", () => {
 
     assert.strictEqual(loadedTicketBoxAccount.currency, null, "currency");
   });
+
+  it("Rejects a uuid longer than 32 bytes", async () => {
+    // no box address exists for a seed this long, so pass any
+    const ticketBoxPda = web3.Keypair.generate().publicKey;
+    const vaultPda = await getPDA(
+      [Buffer.from("vault"), ticketBoxPda.toBuffer()],
+      program.programId
+    );
+    const collectionMint = web3.Keypair.generate();
+    const now = Math.floor(new Date().getTime() / 1000);
+
+    try {
+      await program.methods
+        .initialize(
+          "u".repeat(33),
+          "Flip Girl #0001",
+          COLLECTION_ASSET_URL,
+          new BN(now + 60),
+          new BN(now + 120),
+          new BN(100),
+          new BN(1),
+          new BN(0),
+          true,
+          200,
+          [],
+          { shared: {} },
+          "TICKET",
+          "{name} #{serial}"
+        )
+        .accounts({
+          creator: creator.publicKey,
+          ticketBox: ticketBoxPda,
+          vault: vaultPda,
+          collectionMint: collectionMint.publicKey,
+          collectionTokenAccount: await getAssociatedTokenAddress(
+            collectionMint.publicKey,
+            creator.publicKey
+          ),
+          collectionMetadata: await getMetadataPDA(collectionMint.publicKey),
+          collectionMasterEdition: await getMasterEditionPDA(
+            collectionMint.publicKey
+          ),
+          systemProgram: web3.SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .signers([creator, collectionMint])
+        .rpc();
+      assert.fail("initialized a box with a 33 byte uuid");
+    } catch (err) {
+      assert.include(err.toString(), "UuidTooLong");
+    }
+  });
 });
@@ -0,0 +1,78 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import {
+  getMasterEditionPDA,
+  getMetadataPDA,
+  initTicketBox,
+} from "./fixtures";
+import { TOKEN_METADATA_PROGRAM_ID } from "./contants";
+
+// current `TicketBox` layout version
+const TICKET_BOX_VERSION = 1;
+
+describe("Migrate ticket box", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  it("Creates boxes with the current layout", async () => {
+    const creator = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+    });
+
+    const loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      ticketBox.pda
+    );
+    assert.strictEqual(loadedTicketBoxAccount.version, TICKET_BOX_VERSION);
+    const [, bump] = await web3.PublicKey.findProgramAddress(
+      [
+        Buffer.from("ticket_box"),
+        Buffer.from(ticketBox.id),
+        creator.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    assert.strictEqual(loadedTicketBoxAccount.bump, bump, "bump");
+
+    const collectionMint = web3.Keypair.generate();
+    try {
+      await program.methods
+        .migrateTicketBox()
+        .accounts({
+          creator: creator.publicKey,
+          ticketBox: ticketBox.pda,
+          vault: ticketBox.vault,
+          collectionMint: collectionMint.publicKey,
+          collectionMetadata: await getMetadataPDA(collectionMint.publicKey),
+          collectionMasterEdition: await getMasterEditionPDA(
+            collectionMint.publicKey
+          ),
+          systemProgram: web3.SystemProgram.programId,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        })
+        .signers([creator, collectionMint])
+        .rpc();
+      assert.fail("migrated a current box");
+    } catch (err) {
+      assert.include(err.toString(), "TicketBoxAlreadyMigrated");
+    }
+  });
+});
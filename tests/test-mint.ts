@@ -2,25 +2,22 @@ import {
   workspace,
   Program,
   web3,
-  BN,
   AnchorProvider,
   setProvider,
 } from "@project-serum/anchor";
-import {
-  createAssociatedTokenAccountInstruction,
-  createInitializeMintInstruction,
-  createMintToInstruction,
-  getAssociatedTokenAddress,
-  MINT_SIZE,
-  TOKEN_PROGRAM_ID,
-} from "@solana/spl-token";
+import { getAssociatedTokenAddress } from "@solana/spl-token";
+import { assert } from "chai";
 import { TicketBoxProgram } from "../target/types/ticket_box_program";
-import { getPDA, handleAirdrop } from "./utils";
+import { getSolBalance, getTokenBalance, handleAirdrop } from "./utils";
+import { TOKEN_DECIMALS } from "./contants";
 import {
-  COLLECTION_ASSET_URL,
-  TICKET_ASSET_URL,
-  TOKEN_METADATA_PROGRAM_ID,
-} from "./contants";
+  createCurrency,
+  initTicketBox,
+  mintTicket,
+  SALE_DELAY,
+  TicketBoxFixture,
+  waitUntil,
+} from "./fixtures";
 
 describe("Mint", () => {
   const provider = AnchorProvider.env();
@@ -29,13 +26,7 @@ describe("Mint", () => {
 
   let creator: web3.Keypair;
   let buyer: web3.Keypair;
-  let ticketBoxId = new Date().getTime().toString();
-  // collection
-  let collectionMinKp: web3.Keypair;
-  let collectionMetadataPDA: web3.PublicKey;
-  let collectionMasterEditionPda: web3.PublicKey;
-
-  let ticketMinKp: web3.Keypair;
+  let ticketBox: TicketBoxFixture;
 
   before(async () => {
     creator = web3.Keypair.generate();
@@ -43,262 +34,198 @@ describe("Mint", () => {
     await handleAirdrop(provider, creator.publicKey);
     await handleAirdrop(provider, buyer.publicKey);
 
-    // init box
-    const ticketBoxName = "Flip Girl #0001";
-    const now = Math.floor(new Date().getTime() / 1000);
-    const startAt = now + 1000 * 10;
-    const endAt = startAt + 10 * 60 * 1000;
-
-    const ticketBoxPda = await getPDA(
-      [
-        Buffer.from("ticket_box"),
-        Buffer.from(ticketBoxId),
-        creator.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
-
-    // pre-tx to mint collection
-    const ix = [];
-    collectionMinKp = web3.Keypair.generate();
-
-    const lamports: number =
-      await program.provider.connection.getMinimumBalanceForRentExemption(
-        MINT_SIZE
-      );
-    // create collection token mint
-    ix.push(
-      web3.SystemProgram.createAccount({
-        fromPubkey: creator.publicKey,
-        newAccountPubkey: collectionMinKp.publicKey,
-        space: MINT_SIZE,
-        programId: TOKEN_PROGRAM_ID,
-        lamports,
-      })
-    );
-
-    // init mint token
-    ix.push(
-      createInitializeMintInstruction(
-        collectionMinKp.publicKey,
-        0,
-        creator.publicKey,
-        creator.publicKey
-      )
-    );
+    ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+    });
+    await waitUntil(ticketBox.startAt);
+  });
 
-    const collectionTokenAccount = await getAssociatedTokenAddress(
-      collectionMinKp.publicKey,
-      creator.publicKey
-    );
-    // create collection token account
-    ix.push(
-      createAssociatedTokenAccountInstruction(
-        creator.publicKey,
-        collectionTokenAccount,
-        creator.publicKey,
-        collectionMinKp.publicKey
-      )
-    );
-    // mint 1 token to creator
-    ix.push(
-      createMintToInstruction(
-        collectionMinKp.publicKey,
-        collectionTokenAccount,
-        creator.publicKey,
-        1
-      )
-    );
+  it("Mint", async () => {
+    const { tx } = await mintTicket(program, ticketBox, buyer);
+    console.log("Mint tx hash", tx);
 
-    collectionMetadataPDA = await getPDA(
-      [
-        Buffer.from("metadata"),
-        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-        collectionMinKp.publicKey.toBuffer(),
-      ],
-      TOKEN_METADATA_PROGRAM_ID
+    const loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      ticketBox.pda
     );
-
-    collectionMasterEditionPda = await getPDA(
-      [
-        Buffer.from("metadata"),
-        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-        collectionMinKp.publicKey.toBuffer(),
-        Buffer.from("edition"),
-      ],
-      TOKEN_METADATA_PROGRAM_ID
+    assert.strictEqual(
+      loadedTicketBoxAccount.numOfSold.toNumber(),
+      1,
+      "num_of_sold"
     );
+  });
 
-    const tx = await program.methods
-      .initialize(
-        ticketBoxId,
-        ticketBoxName,
-        COLLECTION_ASSET_URL,
-        new BN(startAt),
-        new BN(endAt), //
-        new BN(100), //
-        new BN(1), //
-        // new BN(0.5 * 10 ** 9), // 0.5 SOL
-        new BN(0),
-        true
-      )
-      .accounts({
-        creator: creator.publicKey,
-        ticketBox: ticketBoxPda,
-        wallet: creator.publicKey,
-        collectionMint: collectionMinKp.publicKey,
-        collectionTokenAccount: collectionTokenAccount,
-        collectionMetadata: collectionMetadataPDA,
-        collectionMasterEdition: collectionMasterEditionPda,
-        systemProgram: web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-      })
-      .preInstructions(ix)
-      .signers([creator, collectionMinKp])
-      .rpc();
+  it("Fails to mint into another box's collection", async () => {
+    const otherTicketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+    });
+    const otherBuyer = web3.Keypair.generate();
+    await handleAirdrop(provider, otherBuyer.publicKey);
+    try {
+      await mintTicket(program, ticketBox, otherBuyer, {
+        collection: otherTicketBox,
+      });
+      assert.fail("minted with a foreign collection");
+    } catch (err) {
+      assert.include(err.toString(), "CollectionMintMismatch");
+    }
+  });
 
-    console.log("Initialize tx hash", tx);
+  it("Fails to mint more than ticket_per_wallet", async () => {
+    try {
+      await mintTicket(program, ticketBox, buyer);
+      assert.fail("minted past ticket_per_wallet");
+    } catch (err) {
+      assert.include(err.toString(), "ExceededTicketPerWallet");
+    }
   });
 
-  it("Mint", async () => {
-    const ix = [];
+  it("Sells out a capped ticket box", async () => {
+    const cappedTicketBox = await initTicketBox(program, creator, {
+      numOfTickets: 2,
+      ticketPerWallet: 1,
+    });
+    await waitUntil(cappedTicketBox.startAt);
+
+    for (let i = 0; i < 2; i++) {
+      const otherBuyer = web3.Keypair.generate();
+      await handleAirdrop(provider, otherBuyer.publicKey);
+      await mintTicket(program, cappedTicketBox, otherBuyer);
+    }
+
+    const loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      cappedTicketBox.pda
+    );
+    assert.strictEqual(
+      loadedTicketBoxAccount.numOfSold.toNumber(),
+      2,
+      "num_of_sold"
+    );
+
+    const lateBuyer = web3.Keypair.generate();
+    await handleAirdrop(provider, lateBuyer.publicKey);
+    try {
+      await mintTicket(program, cappedTicketBox, lateBuyer);
+      assert.fail("minted from a sold out ticket box");
+    } catch (err) {
+      assert.include(err.toString(), "SoldOut");
+    }
+  });
 
-    ticketMinKp = web3.Keypair.generate();
-    const lamports: number =
-      await program.provider.connection.getMinimumBalanceForRentExemption(
-        MINT_SIZE
-      );
+  it("Only mints within the sale window", async () => {
+    const now = Math.floor(new Date().getTime() / 1000);
+    const startAt = now + SALE_DELAY;
+    const endAt = startAt + SALE_DELAY;
+    const windowedTicketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      startAt,
+      endAt,
+    });
+
+    // before start_at
+    try {
+      await mintTicket(program, windowedTicketBox, buyer);
+      assert.fail("minted before start_at");
+    } catch (err) {
+      assert.include(err.toString(), "SaleNotStarted");
+    }
+
+    // during [start_at, end_at)
+    await waitUntil(windowedTicketBox.startAt);
+    await mintTicket(program, windowedTicketBox, buyer);
+
+    // at or after end_at
+    await waitUntil(windowedTicketBox.endAt);
+    try {
+      await mintTicket(program, windowedTicketBox, buyer);
+      assert.fail("minted after end_at");
+    } catch (err) {
+      assert.include(err.toString(), "EventEnded");
+    }
+  });
 
-    ix.push(
-      web3.SystemProgram.createAccount({
-        fromPubkey: buyer.publicKey,
-        newAccountPubkey: ticketMinKp.publicKey,
-        space: MINT_SIZE,
-        programId: TOKEN_PROGRAM_ID,
-        lamports,
-      })
-    );
+  it("Pays the ticket box escrow in SOL", async () => {
+    const price = 0.1 * web3.LAMPORTS_PER_SOL;
+    const paidTicketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      price,
+    });
+    await waitUntil(paidTicketBox.startAt);
+
+    try {
+      await mintTicket(program, paidTicketBox, buyer, {
+        wallet: buyer.publicKey,
+      });
+      assert.fail("paid a wallet other than the escrow");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidEscrow");
+    }
+
+    try {
+      await mintTicket(program, paidTicketBox, buyer, {
+        maxPrice: price - 1,
+      });
+      assert.fail("paid more than the max price");
+    } catch (err) {
+      assert.include(err.toString(), "PriceExceedsMaxPrice");
+    }
+
+    const escrowBalanceBefore = await getSolBalance(
+      paidTicketBox.escrow,
+      provider
+    );
+    await mintTicket(program, paidTicketBox, buyer);
+    const escrowBalanceAfter = await getSolBalance(
+      paidTicketBox.escrow,
+      provider
+    );
+    assert.strictEqual(escrowBalanceAfter - escrowBalanceBefore, price);
+  });
 
-    ix.push(
-      createInitializeMintInstruction(
-        ticketMinKp.publicKey,
-        0,
-        buyer.publicKey,
-        buyer.publicKey
-      )
+  it("Pays the ticket box escrow in SPL currency", async () => {
+    const price = 5 * 10 ** TOKEN_DECIMALS;
+    const currencyMint = await createCurrency(
+      provider,
+      creator,
+      [buyer],
+      10 * price
     );
-
-    const ticketTokenAccount = await getAssociatedTokenAddress(
-      ticketMinKp.publicKey,
+    const buyerTokenAccount = await getAssociatedTokenAddress(
+      currencyMint,
       buyer.publicKey
     );
 
-    ix.push(
-      createAssociatedTokenAccountInstruction(
-        buyer.publicKey,
-        ticketTokenAccount,
-        buyer.publicKey,
-        ticketMinKp.publicKey
-      )
-    );
-
-    ix.push(
-      createMintToInstruction(
-        ticketMinKp.publicKey,
-        ticketTokenAccount,
-        buyer.publicKey,
-        1
-      )
-    );
-
-    const ticketBoxPda = await getPDA(
-      [
-        Buffer.from("ticket_box"),
-        Buffer.from(ticketBoxId),
-        creator.publicKey.toBuffer(),
-      ],
-      program.programId
+    const paidTicketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      price,
+      currency: currencyMint,
+    });
+    await waitUntil(paidTicketBox.startAt);
+
+    try {
+      await mintTicket(program, paidTicketBox, buyer, {
+        wallet: buyerTokenAccount,
+      });
+      assert.fail("paid a token account other than the escrow");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidEscrow");
+    }
+
+    try {
+      await mintTicket(program, paidTicketBox, buyer, { currency: null });
+      assert.fail("paid in an unexpected currency");
+    } catch (err) {
+      assert.include(err.toString(), "CurrencyMismatch");
+    }
+
+    await mintTicket(program, paidTicketBox, buyer);
+    assert.strictEqual(
+      await getTokenBalance(paidTicketBox.escrow, provider),
+      price
     );
-
-    const metadataPDA = await getPDA(
-      [
-        Buffer.from("metadata"),
-        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-        ticketMinKp.publicKey.toBuffer(),
-      ],
-      TOKEN_METADATA_PROGRAM_ID
-    );
-    const masterEditionPda = await getPDA(
-      [
-        Buffer.from("metadata"),
-        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-        ticketMinKp.publicKey.toBuffer(),
-        Buffer.from("edition"),
-      ],
-      TOKEN_METADATA_PROGRAM_ID
-    );
-
-    // collection
-    // const collectionMetadataPDA = await getPDA(
-    //   [
-    //     Buffer.from("metadata"),
-    //     TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-    //     collectionMinKp.publicKey.toBuffer(),
-    //   ],
-    //   TOKEN_METADATA_PROGRAM_ID
-    // );
-
-    // const collectionMasterEditionPda = await getPDA(
-    //   [
-    //     Buffer.from("metadata"),
-    //     TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-    //     collectionMinKp.publicKey.toBuffer(),
-    //     Buffer.from("edition"),
-    //   ],
-    //   TOKEN_METADATA_PROGRAM_ID
-    // );
-
-    // const collectionAuthorityRecordPda = await getPDA(
-    //   [
-    //     Buffer.from("metadata"),
-    //     TOKEN_METADATA_PROGRAM_ID.toBuffer(),
-    //     collectionMinKp.publicKey.toBuffer(),
-    //     Buffer.from("collection_authority"),
-    //     ticketBoxPda.toBuffer(),
-    //   ],
-    //   TOKEN_METADATA_PROGRAM_ID
-    // );
-    // console.log("ticketBoxPda", ticketBoxPda.toBase58());
-    // // console.log("collectionPda", collectionPda.toBase58());
-    // console.log(
-    //   "collectionAuthorityRecordPda",
-    //   collectionAuthorityRecordPda.toBase58()
-    // );
-
-    const tx = await program.methods
-      .mint(TICKET_ASSET_URL)
-      .accounts({
-        payer: buyer.publicKey,
-        ticketBox: ticketBoxPda,
-        wallet: creator.publicKey,
-        ticketMint: ticketMinKp.publicKey,
-        ticketTokenAccount: ticketTokenAccount,
-        ticketMetadata: metadataPDA,
-        ticketMasterEdition: masterEditionPda,
-        collectionMint: collectionMinKp.publicKey,
-        collectionMetadata: collectionMetadataPDA,
-        collectionMasterEdition: collectionMasterEditionPda,
-        systemProgram: web3.SystemProgram.programId,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        rent: web3.SYSVAR_RENT_PUBKEY,
-        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
-      })
-      .preInstructions(ix)
-      .signers([buyer, ticketMinKp])
-      .rpc();
-
-    console.log("Mint tx hash", tx);
   });
 });
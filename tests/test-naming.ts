@@ -0,0 +1,60 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { Metaplex } from "@metaplex-foundation/js";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import { initTicketBox, mintTicket, waitUntil } from "./fixtures";
+
+describe("Ticket naming", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+  const metaplex = Metaplex.make(provider.connection);
+
+  let creator: web3.Keypair;
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+  });
+
+  it("Names tickets and the collection from the box", async () => {
+    const buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, buyer.publicKey);
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+      symbol: "FLIP",
+      nameFormat: "{name} #{serial}",
+    });
+    await waitUntil(ticketBox.startAt);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+
+    const find = (mintAddress: web3.PublicKey) =>
+      metaplex.nfts().findByMint({ mintAddress, loadJsonMetadata: false });
+    const ticketNft = await find(ticket.mint);
+    assert.strictEqual(ticketNft.name, "Flip Girl #0001 #1");
+    assert.strictEqual(ticketNft.symbol, "FLIP");
+    const collectionNft = await find(ticketBox.collectionMint);
+    assert.strictEqual(collectionNft.symbol, "FLIP");
+  });
+
+  it("Rejects a name format that can exceed 32 characters", async () => {
+    try {
+      await initTicketBox(program, creator, {
+        numOfTickets: 100,
+        ticketPerWallet: 1,
+        nameFormat: "{name} - General Entry {serial}",
+      });
+      assert.fail("created a box with ticket names over 32 characters");
+    } catch (err) {
+      assert.include(err.toString(), "NameFormatTooLong");
+    }
+  });
+});
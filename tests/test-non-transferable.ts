@@ -0,0 +1,123 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import {
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  transfer,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import { TOKEN_METADATA_PROGRAM_ID } from "./contants";
+import {
+  initTicketBox,
+  mintTicket,
+  TicketBoxFixture,
+  TicketFixture,
+  waitUntil,
+} from "./fixtures";
+
+describe("Non-transferable", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  let creator: web3.Keypair;
+  let buyer: web3.Keypair;
+  let ticketBox: TicketBoxFixture;
+  let ticket: TicketFixture;
+
+  const thawTicket = () =>
+    program.methods
+      .thawTicket()
+      .accounts({
+        holder: buyer.publicKey,
+        ticketBox: ticketBox.pda,
+        ticketMint: ticket.mint,
+        ticketTokenAccount: ticket.tokenAccount,
+        ticket: ticket.pda,
+        ticketMasterEdition: ticket.masterEdition,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .signers([buyer])
+      .rpc();
+
+  const transferTicket = async () => {
+    const receiver = web3.Keypair.generate();
+    const receiverTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      buyer,
+      ticket.mint,
+      receiver.publicKey
+    );
+    await transfer(
+      provider.connection,
+      buyer,
+      ticket.tokenAccount,
+      receiverTokenAccount.address,
+      buyer,
+      1
+    );
+  };
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+
+    ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+      transferable: false,
+    });
+    await waitUntil(ticketBox.startAt);
+    ticket = await mintTicket(program, ticketBox, buyer);
+  });
+
+  it("Freezes the ticket at mint", async () => {
+    const tokenAccount = await getAccount(
+      provider.connection,
+      ticket.tokenAccount
+    );
+    assert.isTrue(tokenAccount.isFrozen, "frozen");
+
+    try {
+      await transferTicket();
+      assert.fail("transferred a frozen ticket");
+    } catch (err) {
+      assert.include(err.toString(), "0x11");
+    }
+  });
+
+  it("Only thaws a checked in ticket", async () => {
+    try {
+      await thawTicket();
+      assert.fail("thawed an unused ticket");
+    } catch (err) {
+      assert.include(err.toString(), "ThawNotAllowed");
+    }
+
+    await program.methods
+      .checkIn()
+      .accounts({
+        authority: creator.publicKey,
+        ticketBox: ticketBox.pda,
+        ticketMint: ticket.mint,
+        ticketTokenAccount: ticket.tokenAccount,
+        ticket: ticket.pda,
+      })
+      .signers([creator])
+      .rpc();
+
+    await thawTicket();
+    await transferTicket();
+  });
+});
@@ -0,0 +1,169 @@
+import {
+  workspace,
+  Program,
+  web3,
+  BN,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getPDA, handleAirdrop } from "./utils";
+import {
+  initTicketBox,
+  KEEP,
+  mintTicket,
+  TicketBoxFixture,
+  TicketFixture,
+  updateAccounts,
+  waitUntil,
+} from "./fixtures";
+
+// operator permission flags
+const CHECK_IN = 1 << 0;
+const UPDATE_METADATA = 1 << 1;
+
+describe("Operator", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  let creator: web3.Keypair;
+  let operator: web3.Keypair;
+  let operatorPda: web3.PublicKey;
+  let ticketBox: TicketBoxFixture;
+
+  const addOperator = (permissions: number) =>
+    program.methods
+      .addOperator(operator.publicKey, permissions)
+      .accounts({
+        creator: creator.publicKey,
+        ticketBox: ticketBox.pda,
+        operator: operatorPda,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+  const checkIn = (ticket: TicketFixture) =>
+    program.methods
+      .checkIn()
+      .accounts({
+        authority: operator.publicKey,
+        ticketBox: ticketBox.pda,
+        ticketMint: ticket.mint,
+        ticketTokenAccount: ticket.tokenAccount,
+        ticket: ticket.pda,
+      })
+      .remainingAccounts([
+        { pubkey: operatorPda, isSigner: false, isWritable: false },
+      ])
+      .signers([operator])
+      .rpc();
+
+  const update = (name: string | null, price: BN | null) =>
+    program.methods
+      .update(
+        name,
+        null,
+        null,
+        KEEP,
+        KEEP,
+        KEEP,
+        price,
+        null,
+        KEEP,
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts(updateAccounts(ticketBox, operator.publicKey))
+      .remainingAccounts([
+        { pubkey: operatorPda, isSigner: false, isWritable: false },
+      ])
+      .signers([operator])
+      .rpc();
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    operator = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, operator.publicKey);
+
+    ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+    });
+    operatorPda = await getPDA(
+      [
+        Buffer.from("operator"),
+        ticketBox.pda.toBuffer(),
+        operator.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+    await waitUntil(ticketBox.startAt);
+  });
+
+  it("Lets a check-in operator scan tickets", async () => {
+    await addOperator(CHECK_IN);
+
+    const buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, buyer.publicKey);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+    await checkIn(ticket);
+
+    const loadedTicketAccount = await program.account.ticket.fetch(
+      ticket.pda
+    );
+    assert.isNotNull(loadedTicketAccount.checkedInAt, "checked_in_at");
+
+    try {
+      await update("Renamed", null);
+      assert.fail("check-in operator updated metadata");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("Limits metadata operators to name and uri", async () => {
+    await addOperator(CHECK_IN | UPDATE_METADATA);
+
+    await update("Renamed", null);
+    const loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      ticketBox.pda
+    );
+    assert.strictEqual(loadedTicketBoxAccount.name, "Renamed", "name");
+
+    try {
+      await update(null, new BN(1));
+      assert.fail("operator updated the price");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it("Removes an operator", async () => {
+    await program.methods
+      .removeOperator()
+      .accounts({
+        creator: creator.publicKey,
+        ticketBox: ticketBox.pda,
+        operator: operatorPda,
+      })
+      .signers([creator])
+      .rpc();
+
+    const buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, buyer.publicKey);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+    try {
+      await checkIn(ticket);
+      assert.fail("removed operator checked in a ticket");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+});
@@ -0,0 +1,248 @@
+import {
+  workspace,
+  Program,
+  web3,
+  BN,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import {
+  getAssociatedTokenAddress,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getSolBalance, getTokenBalance, handleAirdrop } from "./utils";
+import { TOKEN_DECIMALS, TOKEN_METADATA_PROGRAM_ID } from "./contants";
+import {
+  createCurrency,
+  ensureConfig,
+  initTicketBox,
+  KEEP,
+  mintTicket,
+  TicketBoxFixture,
+  TicketFixture,
+  updateAccounts,
+  waitUntil,
+} from "./fixtures";
+
+describe("Refund", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  let creator: web3.Keypair;
+  let buyer: web3.Keypair;
+
+  const cancelEvent = (box: TicketBoxFixture) =>
+    program.methods
+      .cancelEvent()
+      .accounts({
+        creator: creator.publicKey,
+        ticketBox: box.pda,
+        vault: box.vault,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([creator])
+      .rpc();
+
+  const updateFeeBps = async (feeBps: number) => {
+    const { admin, pda: config } = await ensureConfig(program);
+    await program.methods
+      .updateConfig(null, null, feeBps)
+      .accounts({ admin, config })
+      .rpc();
+  };
+
+  const refund = async (box: TicketBoxFixture, ticket: TicketFixture) =>
+    program.methods
+      .refund()
+      .accounts({
+        holder: buyer.publicKey,
+        ticketBox: box.pda,
+        ticketMint: ticket.mint,
+        ticketTokenAccount: ticket.tokenAccount,
+        ticket: ticket.pda,
+        ticketMetadata: ticket.metadata,
+        ticketMasterEdition: ticket.masterEdition,
+        collectionMetadata: box.collectionMetadata,
+        vault: box.vault,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        box.currency
+          ? [
+              // vault token account
+              { pubkey: box.escrow, isSigner: false, isWritable: true },
+              // holder token account
+              {
+                pubkey: await getAssociatedTokenAddress(
+                  box.currency,
+                  buyer.publicKey
+                ),
+                isSigner: false,
+                isWritable: true,
+              },
+            ]
+          : []
+      )
+      .signers([buyer])
+      .rpc();
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+  });
+
+  it("Refunds SOL tickets of a cancelled event", async () => {
+    const price = 0.1 * web3.LAMPORTS_PER_SOL;
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      price,
+    });
+    await waitUntil(ticketBox.startAt);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+
+    try {
+      await refund(ticketBox, ticket);
+      assert.fail("refunded a live event");
+    } catch (err) {
+      assert.include(err.toString(), "EventNotCancelled");
+    }
+
+    await cancelEvent(ticketBox);
+
+    try {
+      await mintTicket(program, ticketBox, buyer);
+      assert.fail("minted from a cancelled event");
+    } catch (err) {
+      assert.include(err.toString(), "EventCancelled");
+    }
+
+    const vaultBalanceBefore = await getSolBalance(ticketBox.vault, provider);
+    await refund(ticketBox, ticket);
+    const vaultBalanceAfter = await getSolBalance(ticketBox.vault, provider);
+    assert.strictEqual(vaultBalanceBefore - vaultBalanceAfter, price);
+    // the burn closes every account of the ticket NFT
+    for (const pubkey of [
+      ticket.tokenAccount,
+      ticket.metadata,
+      ticket.masterEdition,
+      ticket.pda,
+    ]) {
+      assert.isNull(await provider.connection.getAccountInfo(pubkey));
+    }
+  });
+
+  it("Refunds the full price when the treasury took a fee", async () => {
+    const price = 0.1 * web3.LAMPORTS_PER_SOL;
+    const fee = (price * 500) / 10000;
+    await updateFeeBps(500);
+    try {
+      const ticketBox = await initTicketBox(program, creator, {
+        numOfTickets: 100,
+        ticketPerWallet: 10,
+        price,
+      });
+      await waitUntil(ticketBox.startAt);
+      const ticket = await mintTicket(program, ticketBox, buyer);
+
+      // the escrow got the price minus the fee, the creator puts the fee back
+      const creatorBalanceBefore = await getSolBalance(
+        creator.publicKey,
+        provider
+      );
+      await cancelEvent(ticketBox);
+      const creatorBalanceAfter = await getSolBalance(
+        creator.publicKey,
+        provider
+      );
+      assert.isAtLeast(creatorBalanceBefore - creatorBalanceAfter, fee);
+
+      const buyerBalanceBefore = await getSolBalance(buyer.publicKey, provider);
+      const vaultBalanceBefore = await getSolBalance(ticketBox.vault, provider);
+      await refund(ticketBox, ticket);
+      const vaultBalanceAfter = await getSolBalance(ticketBox.vault, provider);
+      const buyerBalanceAfter = await getSolBalance(buyer.publicKey, provider);
+      assert.strictEqual(vaultBalanceBefore - vaultBalanceAfter, price);
+      // the closed ticket PDA returns its rent on top
+      assert.isAtLeast(buyerBalanceAfter - buyerBalanceBefore, price);
+    } finally {
+      // other suites expect the full price in the escrow
+      await updateFeeBps(0);
+    }
+  });
+
+  it("Refunds SPL tickets at the price paid", async () => {
+    const price = 5 * 10 ** TOKEN_DECIMALS;
+    const currencyMint = await createCurrency(
+      provider,
+      creator,
+      [buyer],
+      10 * price
+    );
+    const buyerTokenAccount = await getAssociatedTokenAddress(
+      currencyMint,
+      buyer.publicKey
+    );
+
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      price,
+      currency: currencyMint,
+    });
+    await waitUntil(ticketBox.startAt);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+
+    // a later price change doesn't affect the refund
+    await program.methods
+      .update(
+        null,
+        null,
+        null,
+        KEEP,
+        KEEP,
+        KEEP,
+        new BN(2 * price),
+        null,
+        KEEP,
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts(updateAccounts(ticketBox, creator.publicKey))
+      .signers([creator])
+      .rpc();
+
+    await cancelEvent(ticketBox);
+    await refund(ticketBox, ticket);
+    assert.strictEqual(
+      await getTokenBalance(buyerTokenAccount, provider),
+      10 * price
+    );
+    assert.strictEqual(await getTokenBalance(ticketBox.escrow, provider), 0);
+  });
+
+  it("Refunds a frozen non-transferable ticket", async () => {
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      transferable: false,
+    });
+    await waitUntil(ticketBox.startAt);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+
+    await cancelEvent(ticketBox);
+    await refund(ticketBox, ticket);
+    assert.isNull(await provider.connection.getAccountInfo(ticket.metadata));
+  });
+});
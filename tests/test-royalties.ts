@@ -0,0 +1,72 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { Metaplex } from "@metaplex-foundation/js";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import { initTicketBox, mintTicket, waitUntil } from "./fixtures";
+
+describe("Royalties", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+  const metaplex = Metaplex.make(provider.connection);
+
+  it("Mints tickets with the box royalties", async () => {
+    const creator = web3.Keypair.generate();
+    const buyer = web3.Keypair.generate();
+    const partner = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+      sellerFeeBasisPoints: 500,
+      creators: [
+        { address: creator.publicKey, share: 70 },
+        { address: partner.publicKey, share: 30 },
+      ],
+    });
+    await waitUntil(ticketBox.startAt);
+    const ticket = await mintTicket(program, ticketBox, buyer);
+
+    for (const mintAddress of [ticketBox.collectionMint, ticket.mint]) {
+      const nft = await metaplex
+        .nfts()
+        .findByMint({ mintAddress, loadJsonMetadata: false });
+      assert.strictEqual(nft.sellerFeeBasisPoints, 500);
+      const creators = nft.creators.map(({ address, verified, share }) => ({
+        address: address.toBase58(),
+        verified,
+        share,
+      }));
+      assert.deepEqual(creators, [
+        { address: ticketBox.pda.toBase58(), verified: true, share: 0 },
+        { address: creator.publicKey.toBase58(), verified: false, share: 70 },
+        { address: partner.publicKey.toBase58(), verified: false, share: 30 },
+      ]);
+    }
+  });
+
+  it("Rejects creator shares not summing to 100", async () => {
+    const creator = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+
+    try {
+      await initTicketBox(program, creator, {
+        numOfTickets: 100,
+        ticketPerWallet: 1,
+        creators: [{ address: creator.publicKey, share: 90 }],
+      });
+      assert.fail("created a box with 90% of royalties assigned");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCreators");
+    }
+  });
+});
@@ -0,0 +1,145 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { Metaplex } from "@metaplex-foundation/js";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import {
+  initTicketBox,
+  KEEP,
+  mintTicket,
+  TicketBoxFixture,
+  TicketFixture,
+  updateAccounts,
+  waitUntil,
+} from "./fixtures";
+import { TOKEN_METADATA_PROGRAM_ID } from "./contants";
+
+describe("Ticket metadata", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+  const metaplex = Metaplex.make(provider.connection);
+
+  let creator: web3.Keypair;
+  let ticketBox: TicketBoxFixture;
+  let tickets: TicketFixture[];
+
+  const findTicketNft = (ticket: TicketFixture) =>
+    metaplex
+      .nfts()
+      .findByMint({ mintAddress: ticket.mint, loadJsonMetadata: false });
+
+  const updateTicketMetadata = (
+    nameFormat: string | null,
+    uri: string | null,
+    authority = creator
+  ) =>
+    program.methods
+      .updateTicketMetadata(nameFormat, uri)
+      .accounts({
+        creator: authority.publicKey,
+        ticketBox: ticketBox.pda,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        tickets.flatMap((ticket) => [
+          { pubkey: ticket.metadata, isWritable: true, isSigner: false },
+          { pubkey: ticket.pda, isWritable: false, isSigner: false },
+        ])
+      )
+      .signers([authority])
+      .rpc();
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+      symbol: "FLIP",
+      sellerFeeBasisPoints: 500,
+      nameFormat: "{name} #{serial}",
+    });
+    await waitUntil(ticketBox.startAt);
+    tickets = [];
+    for (let i = 0; i < 2; i++) {
+      const buyer = web3.Keypair.generate();
+      await handleAirdrop(provider, buyer.publicKey);
+      tickets.push(await mintTicket(program, ticketBox, buyer));
+    }
+  });
+
+  it("Only lets the creator update ticket metadata", async () => {
+    const stranger = web3.Keypair.generate();
+    await handleAirdrop(provider, stranger.publicKey);
+    try {
+      await updateTicketMetadata(null, null, stranger);
+      assert.fail("updated ticket metadata without being the creator");
+    } catch (err) {
+      assert.match(err.toString(), /ConstraintHasOne|ConstraintSeeds/);
+    }
+  });
+
+  it("Rewrites uris and keeps ticket royalties", async () => {
+    // the box moves on, sold tickets keep their symbol and royalties
+    await program.methods
+      .update(
+        null,
+        null,
+        null,
+        KEEP,
+        KEEP,
+        KEEP,
+        null,
+        null,
+        KEEP,
+        1000,
+        null,
+        null,
+        "NEW",
+        null
+      )
+      .accounts(updateAccounts(ticketBox, creator.publicKey))
+      .signers([creator])
+      .rpc();
+
+    await updateTicketMetadata(null, "https://example.com/moved/{serial}.json");
+
+    for (const [i, ticket] of tickets.entries()) {
+      const ticketNft = await findTicketNft(ticket);
+      assert.strictEqual(ticketNft.name, `Flip Girl #0001 #${i + 1}`);
+      assert.strictEqual(
+        ticketNft.uri,
+        `https://example.com/moved/${i + 1}.json`
+      );
+      assert.strictEqual(ticketNft.symbol, "FLIP");
+      assert.strictEqual(ticketNft.sellerFeeBasisPoints, 500);
+    }
+  });
+
+  it("Renames tickets with a new name format", async () => {
+    await updateTicketMetadata("{name} No. {serial}", null);
+
+    for (const [i, ticket] of tickets.entries()) {
+      const ticketNft = await findTicketNft(ticket);
+      assert.strictEqual(ticketNft.name, `Flip Girl #0001 No. ${i + 1}`);
+      assert.strictEqual(
+        ticketNft.uri,
+        `https://example.com/moved/${i + 1}.json`
+      );
+    }
+
+    try {
+      await updateTicketMetadata("{name} - General Entry {serial}", null);
+      assert.fail("renamed tickets past 32 characters");
+    } catch (err) {
+      assert.include(err.toString(), "NameFormatTooLong");
+    }
+  });
+});
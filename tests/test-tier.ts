@@ -0,0 +1,167 @@
+import {
+  workspace,
+  Program,
+  web3,
+  BN,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getPDA, getSolBalance, handleAirdrop } from "./utils";
+import { TICKET_ASSET_URL } from "./contants";
+import {
+  initTicketBox,
+  KEEP,
+  mintTicket,
+  set,
+  TicketBoxFixture,
+  waitUntil,
+} from "./fixtures";
+
+describe("Ticket tiers", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  const vipPrice = 0.2 * web3.LAMPORTS_PER_SOL;
+  const vipUri = `${TICKET_ASSET_URL}?tier=vip`;
+
+  let creator: web3.Keypair;
+  let buyer: web3.Keypair;
+  let ticketBox: TicketBoxFixture;
+  let gaTier: web3.PublicKey;
+  let vipTier: web3.PublicKey;
+
+  const getTierPDA = (index: number) =>
+    getPDA(
+      [
+        Buffer.from("ticket_tier"),
+        ticketBox.pda.toBuffer(),
+        Buffer.from([index]),
+      ],
+      program.programId
+    );
+
+  const addTier = async (
+    name: string,
+    uri: string,
+    numOfTickets: number,
+    ticketPerWallet: number,
+    price: number
+  ) => {
+    const { numOfTiers } = await program.account.ticketBox.fetch(
+      ticketBox.pda
+    );
+    const tier = await getTierPDA(numOfTiers);
+    await program.methods
+      .addTier(
+        name,
+        uri,
+        new BN(ticketBox.startAt),
+        null,
+        new BN(numOfTickets),
+        new BN(ticketPerWallet),
+        new BN(price)
+      )
+      .accounts({
+        creator: creator.publicKey,
+        ticketBox: ticketBox.pda,
+        ticketTier: tier,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+    return tier;
+  };
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+
+    ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+    });
+    gaTier = await addTier("GA", TICKET_ASSET_URL, 50, 5, 0);
+    vipTier = await addTier("VIP", vipUri, 2, 1, vipPrice);
+    await waitUntil(ticketBox.startAt);
+  });
+
+  it("Requires a tier once the box has tiers", async () => {
+    try {
+      await mintTicket(program, ticketBox, buyer);
+      assert.fail("minted without a tier");
+    } catch (err) {
+      assert.include(err.toString(), "TicketTierRequired");
+    }
+  });
+
+  it("Mints at the tier price and uri", async () => {
+    const vaultBalanceBefore = await getSolBalance(ticketBox.vault, provider);
+    const ticket = await mintTicket(program, ticketBox, buyer, {
+      tier: vipTier,
+    });
+    const vaultBalanceAfter = await getSolBalance(ticketBox.vault, provider);
+    assert.strictEqual(vaultBalanceAfter - vaultBalanceBefore, vipPrice);
+
+    const loadedTicketAccount = await program.account.ticket.fetch(
+      ticket.pda
+    );
+    assert.strictEqual(loadedTicketAccount.tier, 1, "tier");
+    assert.strictEqual(loadedTicketAccount.price.toNumber(), vipPrice);
+
+    const loadedTierAccount = await program.account.ticketTier.fetch(vipTier);
+    assert.strictEqual(loadedTierAccount.numOfSold.toNumber(), 1);
+    assert.strictEqual(loadedTierAccount.uri, vipUri);
+  });
+
+  it("Enforces the tier per-wallet cap", async () => {
+    try {
+      await mintTicket(program, ticketBox, buyer, { tier: vipTier });
+      assert.fail("exceeded the tier per-wallet cap");
+    } catch (err) {
+      assert.include(err.toString(), "ExceededTicketPerWallet");
+    }
+
+    // other tiers keep their own cap
+    await mintTicket(program, ticketBox, buyer, { tier: gaTier });
+  });
+
+  it("Sells a tier out independently of the box", async () => {
+    const other = web3.Keypair.generate();
+    await handleAirdrop(provider, other.publicKey);
+    await mintTicket(program, ticketBox, other, { tier: vipTier });
+
+    try {
+      await mintTicket(program, ticketBox, other, { tier: vipTier });
+      assert.fail("minted from a sold out tier");
+    } catch (err) {
+      assert.include(err.toString(), "SoldOut");
+    }
+
+    const loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      ticketBox.pda
+    );
+    assert.strictEqual(loadedTicketBoxAccount.numOfSold.toNumber(), 3);
+  });
+
+  it("Keeps tier supply above the tickets sold", async () => {
+    try {
+      await program.methods
+        .updateTier(null, null, null, KEEP, set(1), KEEP, null)
+        .accounts({
+          creator: creator.publicKey,
+          ticketBox: ticketBox.pda,
+          ticketTier: vipTier,
+        })
+        .signers([creator])
+        .rpc();
+      assert.fail("reduced supply below sold");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidNumOfTickets");
+    }
+  });
+});
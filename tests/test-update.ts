@@ -0,0 +1,271 @@
+import {
+  workspace,
+  Program,
+  web3,
+  BN,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { Metaplex } from "@metaplex-foundation/js";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getAssociatedTokenAddress } from "@solana/spl-token";
+import { getTokenBalance, handleAirdrop } from "./utils";
+import { TOKEN_DECIMALS } from "./contants";
+import {
+  CLEAR,
+  createCurrency,
+  initTicketBox,
+  KEEP,
+  mintTicket,
+  OptionUpdate,
+  set,
+  SOL,
+  TicketBoxFixture,
+  token,
+  updateAccounts,
+  waitUntil,
+} from "./fixtures";
+
+describe("Update", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  let creator: web3.Keypair;
+  let ticketBox: TicketBoxFixture;
+
+  const updateMetadata = (name: string | null, uri: string | null) =>
+    program.methods
+      .update(
+        name,
+        uri,
+        null,
+        KEEP,
+        KEEP,
+        KEEP,
+        null,
+        null,
+        KEEP,
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts(updateAccounts(ticketBox, creator.publicKey))
+      .signers([creator])
+      .rpc();
+
+  const updateLimits = (
+    endAt: OptionUpdate,
+    numOfTickets: OptionUpdate,
+    ticketPerWallet: OptionUpdate
+  ) =>
+    program.methods
+      .update(
+        null,
+        null,
+        null,
+        endAt,
+        numOfTickets,
+        ticketPerWallet,
+        null,
+        null,
+        KEEP,
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts(updateAccounts(ticketBox, creator.publicKey))
+      .signers([creator])
+      .rpc();
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+
+    ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 1,
+    });
+  });
+
+  it("Rejects a name over 32 characters", async () => {
+    try {
+      await updateMetadata("n".repeat(33), null);
+      assert.fail("set a 33 character name");
+    } catch (err) {
+      assert.include(err.toString(), "NameTooLong");
+    }
+  });
+
+  it("Rejects a uri over 200 characters", async () => {
+    try {
+      await updateMetadata(null, "u".repeat(201));
+      assert.fail("set a 201 character uri");
+    } catch (err) {
+      assert.include(err.toString(), "UriTooLong");
+    }
+  });
+
+  it("Renames the collection NFT", async () => {
+    await updateMetadata("Flip Girl Live", null);
+
+    const collectionNft = await Metaplex.make(provider.connection)
+      .nfts()
+      .findByMint({
+        mintAddress: ticketBox.collectionMint,
+        loadJsonMetadata: false,
+      });
+    assert.strictEqual(collectionNft.name, "Flip Girl Live");
+  });
+
+  it("Clears limits back to unlimited", async () => {
+    await updateLimits(CLEAR, CLEAR, KEEP);
+
+    const loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      ticketBox.pda
+    );
+    assert.isNull(loadedTicketBoxAccount.endAt, "end_at");
+    assert.isNull(loadedTicketBoxAccount.numOfTickets, "num_of_tickets");
+    assert.strictEqual(
+      loadedTicketBoxAccount.ticketPerWallet.toNumber(),
+      1,
+      "ticket_per_wallet"
+    );
+  });
+
+  it("Keeps supply above the tickets sold", async () => {
+    await waitUntil(ticketBox.startAt);
+    for (let i = 0; i < 2; i++) {
+      const buyer = web3.Keypair.generate();
+      await handleAirdrop(provider, buyer.publicKey);
+      await mintTicket(program, ticketBox, buyer);
+    }
+
+    try {
+      await updateLimits(KEEP, set(1), KEEP);
+      assert.fail("reduced supply below sold");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidNumOfTickets");
+    }
+
+    await updateLimits(KEEP, set(2), KEEP);
+  });
+
+  it("Locks the start once the sale opened", async () => {
+    try {
+      await program.methods
+        .update(
+          null,
+          null,
+          new BN(ticketBox.startAt + 60),
+          KEEP,
+          KEEP,
+          KEEP,
+          null,
+          null,
+          KEEP,
+          null,
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts(updateAccounts(ticketBox, creator.publicKey))
+        .signers([creator])
+        .rpc();
+      assert.fail("moved the start of an open sale");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidTicketBoxStartAt");
+    }
+  });
+
+  it("Switches currency until the first sale", async () => {
+    const price = 5 * 10 ** TOKEN_DECIMALS;
+    const buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, buyer.publicKey);
+    const currencyMint = await createCurrency(
+      provider,
+      creator,
+      [buyer],
+      10 * price
+    );
+    const solTicketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      price,
+    });
+    const escrow = await getAssociatedTokenAddress(
+      currencyMint,
+      solTicketBox.vault,
+      true
+    );
+
+    const updateCurrency = (
+      currency: typeof SOL | ReturnType<typeof token>,
+      remainingAccounts: web3.PublicKey[]
+    ) =>
+      program.methods
+        .update(
+          null,
+          null,
+          null,
+          KEEP,
+          KEEP,
+          KEEP,
+          null,
+          null,
+          currency,
+          null,
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts(updateAccounts(solTicketBox, creator.publicKey))
+        .remainingAccounts(
+          remainingAccounts.map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: true,
+          }))
+        )
+        .signers([creator])
+        .rpc();
+
+    await updateCurrency(token(currencyMint), [currencyMint, escrow]);
+    assert.isNotNull(await provider.connection.getAccountInfo(escrow));
+
+    // switching back closes the vault token account
+    await updateCurrency(SOL, [escrow]);
+    assert.isNull(await provider.connection.getAccountInfo(escrow));
+    let loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      solTicketBox.pda
+    );
+    assert.isNull(loadedTicketBoxAccount.currency);
+    assert.isTrue(loadedTicketBoxAccount.escrow.equals(solTicketBox.vault));
+
+    await updateCurrency(token(currencyMint), [currencyMint, escrow]);
+    loadedTicketBoxAccount = await program.account.ticketBox.fetch(
+      solTicketBox.pda
+    );
+    assert.isTrue(loadedTicketBoxAccount.currency.equals(currencyMint));
+    assert.isTrue(loadedTicketBoxAccount.escrow.equals(escrow));
+
+    const tokenTicketBox = { ...solTicketBox, currency: currencyMint, escrow };
+    await waitUntil(tokenTicketBox.startAt);
+    await mintTicket(program, tokenTicketBox, buyer);
+    assert.strictEqual(await getTokenBalance(escrow, provider), price);
+
+    try {
+      await updateCurrency(SOL, [escrow]);
+      assert.fail("switched currency after a sale");
+    } catch (err) {
+      assert.include(err.toString(), "CurrencyLocked");
+    }
+  });
+});
@@ -0,0 +1,85 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import { Metaplex } from "@metaplex-foundation/js";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { handleAirdrop } from "./utils";
+import { TICKET_ASSET_URL } from "./contants";
+import {
+  CREATOR_SIGNED_URI,
+  initTicketBox,
+  mintTicket,
+  TEMPLATE_URI,
+  waitUntil,
+} from "./fixtures";
+
+describe("Ticket uri", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+  const metaplex = Metaplex.make(provider.connection);
+
+  let creator: web3.Keypair;
+  let buyer: web3.Keypair;
+
+  const getUri = async (mintAddress: web3.PublicKey) => {
+    const nft = await metaplex
+      .nfts()
+      .findByMint({ mintAddress, loadJsonMetadata: false });
+    return nft.uri;
+  };
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+  });
+
+  it("Fills the serial into a template uri", async () => {
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      uri: `${TICKET_ASSET_URL}/{serial}.json`,
+      uriMode: TEMPLATE_URI,
+    });
+    await waitUntil(ticketBox.startAt);
+
+    await mintTicket(program, ticketBox, buyer);
+    const ticket = await mintTicket(program, ticketBox, buyer, {
+      uri: "https://example.com/ignored.json",
+    });
+    assert.strictEqual(
+      await getUri(ticket.mint),
+      `${TICKET_ASSET_URL}/2.json`
+    );
+  });
+
+  it("Only takes a custom uri signed by the creator", async () => {
+    const uri = "https://example.com/seat-a12.json";
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      uriMode: CREATOR_SIGNED_URI,
+    });
+    await waitUntil(ticketBox.startAt);
+
+    try {
+      await mintTicket(program, ticketBox, buyer, { uri });
+      assert.fail("minted a custom uri without the creator");
+    } catch (err) {
+      assert.include(err.toString(), "CreatorSignatureRequired");
+    }
+
+    const ticket = await mintTicket(program, ticketBox, buyer, {
+      uri,
+      uriSigner: creator,
+    });
+    assert.strictEqual(await getUri(ticket.mint), uri);
+  });
+});
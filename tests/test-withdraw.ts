@@ -0,0 +1,180 @@
+import {
+  workspace,
+  Program,
+  web3,
+  AnchorProvider,
+  setProvider,
+} from "@project-serum/anchor";
+import {
+  getAssociatedTokenAddress,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { TicketBoxProgram } from "../target/types/ticket_box_program";
+import { getSolBalance, getTokenBalance, handleAirdrop } from "./utils";
+import { TOKEN_DECIMALS } from "./contants";
+import {
+  createCurrency,
+  initTicketBox,
+  mintTicket,
+  KEEP,
+  SALE_DELAY,
+  set,
+  TicketBoxFixture,
+  updateAccounts,
+  waitUntil,
+} from "./fixtures";
+
+describe("Withdraw", () => {
+  const provider = AnchorProvider.env();
+  setProvider(provider);
+  const program = workspace.TicketBoxProgram as Program<TicketBoxProgram>;
+
+  let creator: web3.Keypair;
+  let buyer: web3.Keypair;
+
+  const withdraw = (
+    box: TicketBoxFixture,
+    creatorTokenAccount?: web3.PublicKey
+  ) =>
+    program.methods
+      .withdraw()
+      .accounts({
+        creator: creator.publicKey,
+        ticketBox: box.pda,
+        vault: box.vault,
+        systemProgram: web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        box.currency
+          ? [
+              // vault token account
+              { pubkey: box.escrow, isSigner: false, isWritable: true },
+              // creator token account
+              {
+                pubkey: creatorTokenAccount,
+                isSigner: false,
+                isWritable: true,
+              },
+            ]
+          : []
+      )
+      .signers([creator])
+      .rpc();
+
+  before(async () => {
+    creator = web3.Keypair.generate();
+    buyer = web3.Keypair.generate();
+    await handleAirdrop(provider, creator.publicKey);
+    await handleAirdrop(provider, buyer.publicKey);
+  });
+
+  it("Withdraws SOL after end_at", async () => {
+    const price = 0.1 * web3.LAMPORTS_PER_SOL;
+    const now = Math.floor(new Date().getTime() / 1000);
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      startAt: now + SALE_DELAY,
+      endAt: now + 2 * SALE_DELAY,
+      price,
+    });
+    await waitUntil(ticketBox.startAt);
+    await mintTicket(program, ticketBox, buyer);
+
+    try {
+      await withdraw(ticketBox);
+      assert.fail("withdrew before end_at");
+    } catch (err) {
+      assert.include(err.toString(), "EventNotEnded");
+    }
+
+    await waitUntil(ticketBox.endAt);
+    const vaultBalanceBefore = await getSolBalance(ticketBox.vault, provider);
+    await withdraw(ticketBox);
+    const vaultBalanceAfter = await getSolBalance(ticketBox.vault, provider);
+    assert.strictEqual(vaultBalanceBefore - vaultBalanceAfter, price);
+  });
+
+  it("Locks the sale once proceeds are withdrawn", async () => {
+    const now = Math.floor(new Date().getTime() / 1000);
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      startAt: now + SALE_DELAY,
+      endAt: now + 2 * SALE_DELAY,
+      price: 0.1 * web3.LAMPORTS_PER_SOL,
+    });
+    await waitUntil(ticketBox.startAt);
+    await mintTicket(program, ticketBox, buyer);
+    await waitUntil(ticketBox.endAt);
+    await withdraw(ticketBox);
+
+    // reopening the sale would let a cancel refund proceeds already withdrawn
+    const later = Math.floor(new Date().getTime() / 1000) + 60;
+    try {
+      await program.methods
+        .update(
+          null,
+          null,
+          null,
+          set(later),
+          KEEP,
+          KEEP,
+          null,
+          null,
+          KEEP,
+          null,
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts(updateAccounts(ticketBox, creator.publicKey))
+        .signers([creator])
+        .rpc();
+      assert.fail("moved end_at after a withdrawal");
+    } catch (err) {
+      assert.include(err.toString(), "ProceedsAlreadyWithdrawn");
+    }
+  });
+
+  it("Withdraws SPL currency after end_at", async () => {
+    const price = 5 * 10 ** TOKEN_DECIMALS;
+    const currencyMint = await createCurrency(
+      provider,
+      creator,
+      [buyer, creator],
+      10 * price
+    );
+    const creatorTokenAccount = await getAssociatedTokenAddress(
+      currencyMint,
+      creator.publicKey
+    );
+
+    const now = Math.floor(new Date().getTime() / 1000);
+    const ticketBox = await initTicketBox(program, creator, {
+      numOfTickets: 100,
+      ticketPerWallet: 10,
+      startAt: now + SALE_DELAY,
+      endAt: now + 2 * SALE_DELAY,
+      price,
+      currency: currencyMint,
+    });
+    await waitUntil(ticketBox.startAt);
+    await mintTicket(program, ticketBox, buyer);
+    assert.strictEqual(
+      await getTokenBalance(ticketBox.escrow, provider),
+      price
+    );
+
+    await waitUntil(ticketBox.endAt);
+    await withdraw(ticketBox, creatorTokenAccount);
+    assert.strictEqual(await getTokenBalance(ticketBox.escrow, provider), 0);
+    assert.strictEqual(
+      await getTokenBalance(creatorTokenAccount, provider),
+      11 * price
+    );
+  });
+});
@@ -42,3 +42,6 @@ export const getSolBalance = async (
 ) => {
   return await provider.connection.getBalance(pubkey);
 };
+
+export const sleep = (seconds: number) =>
+  new Promise((resolve) => setTimeout(resolve, seconds * 1000));